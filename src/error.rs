@@ -2,6 +2,7 @@ use alloc::alloc::Layout;
 use core::{
     fmt::{Display, Debug, Formatter, Result as FmtResult},
     ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 /// Errors for allocation operations.
@@ -24,6 +25,9 @@ pub enum AllocError {
     /// An arithmetic operation overflowed. This error contains the left-hand and right-hand side
     /// values as well as the operation.
     ArithmeticOverflow(usize, ArithOp, usize),
+    /// An allocation would exceed a memory limit. This error contains the limit, the number of
+    /// bytes in use and the number of bytes requested.
+    LimitExceeded(usize, usize, usize),
     /// Any other kind of error, in the form of a string.
     Other(&'static str),
 }
@@ -32,8 +36,8 @@ pub enum AllocError {
 impl PartialEq for AllocError {
     fn eq(&self, other: &Self) -> bool {
         use AllocError::{
-            AllocFailed, GrowSmallerNewLayout, LayoutError, Other, ShrinkBiggerNewLayout,
-            ZeroSizedLayout,
+            AllocFailed, GrowSmallerNewLayout, LayoutError, LimitExceeded, Other,
+            ShrinkBiggerNewLayout, ZeroSizedLayout,
         };
 
         match (self, other) {
@@ -44,6 +48,9 @@ impl PartialEq for AllocError {
             | (ShrinkBiggerNewLayout(old1, new1), ShrinkBiggerNewLayout(old2, new2)) => {
                 old1 == old2 && new1 == new2
             }
+            (LimitExceeded(lim1, used1, req1), LimitExceeded(lim2, used2, req2)) => {
+                lim1 == lim2 && used1 == used2 && req1 == req2
+            }
             (Other(a), Other(b)) => a == b,
             _ => false,
         }
@@ -74,6 +81,10 @@ impl Display for AllocError {
                 f,
                 "arithmetic operation overflowed: {lhs} {op} {rhs}"
             ),
+            AllocError::LimitExceeded(limit, used, requested) => write!(
+                f,
+                "allocation of {requested} bytes exceeds the limit of {limit} bytes ({used} in use)"
+            ),
             AllocError::Other(other) => write!(f, "{other}"),
         }
     }
@@ -108,3 +119,92 @@ impl Display for ArithOp {
         }
     }
 }
+
+/// A kind of memory misuse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// Bytes before the block were overwritten.
+    Underflow,
+    /// Bytes after the block were overwritten.
+    Overflow,
+    /// The block was written to after it was deallocated.
+    UseAfterFree,
+    /// The layout given for the block differs from the one it was allocated with, which is
+    /// contained.
+    LayoutMismatch(Layout),
+    /// The pointer doesn't point to a block from the allocator.
+    UnknownPointer,
+    /// The block was already deallocated.
+    DoubleFree,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ViolationKind::Underflow => write!(f, "buffer underflow"),
+            ViolationKind::Overflow => write!(f, "buffer overflow"),
+            ViolationKind::UseAfterFree => write!(f, "use after free"),
+            ViolationKind::LayoutMismatch(l) => {
+                write!(f, "layout mismatch (allocated with layout {l:?})")
+            }
+            ViolationKind::UnknownPointer => write!(f, "unknown pointer"),
+            ViolationKind::DoubleFree => write!(f, "double free"),
+        }
+    }
+}
+
+/// A memory misuse detected by a debugging allocator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    /// What went wrong.
+    pub kind: ViolationKind,
+    /// The pointer to the affected block.
+    pub ptr: NonNull<u8>,
+    /// The layout of the affected block.
+    pub layout: Layout,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} detected in block at {:p} with layout {:?}",
+            self.kind, self.ptr, self.layout
+        )
+    }
+}
+
+/// A hook which debugging allocators report [`Violation`]s to.
+///
+/// This is implemented for [`PanicOnViolation`], for [`AtomicUsize`] as a counter, and for any
+/// `Fn(&Violation)`, e.g. to log violations.
+pub trait ViolationHandler {
+    /// Reports a violation.
+    fn report(&self, violation: &Violation);
+}
+
+/// A [`ViolationHandler`] which panics on any violation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PanicOnViolation;
+
+impl ViolationHandler for PanicOnViolation {
+    #[track_caller]
+    fn report(&self, violation: &Violation) {
+        panic!("{violation}");
+    }
+}
+
+// violation counter
+impl ViolationHandler for AtomicUsize {
+    #[inline]
+    fn report(&self, _: &Violation) {
+        self.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl<F: Fn(&Violation)> ViolationHandler for F {
+    #[inline]
+    fn report(&self, violation: &Violation) {
+        self(violation);
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     external_alloc::REALLOC_DIFF_ALIGN,
     external_alloc::{ffi::jem as ffi, resize},
     helpers::{null_q, zsl_check},
-    Alloc,
+    Alloc, Owns,
 };
 use core::{
     alloc::{GlobalAlloc, Layout},
@@ -26,7 +26,7 @@ macro_rules! assume {
 pub struct Jemalloc;
 
 unsafe impl GlobalAlloc for Jemalloc {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         assume!(layout.size() != 0);
@@ -38,7 +38,7 @@ unsafe impl GlobalAlloc for Jemalloc {
         }) as *mut u8
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         assume!(!ptr.is_null());
@@ -50,7 +50,7 @@ unsafe impl GlobalAlloc for Jemalloc {
         );
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
         assume!(layout.size() != 0);
@@ -76,7 +76,7 @@ unsafe impl GlobalAlloc for Jemalloc {
 }
 
 impl Alloc for Jemalloc {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         zsl_check(layout, |layout| {
@@ -92,7 +92,7 @@ impl Alloc for Jemalloc {
         })
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         zsl_check(layout, |layout| {
@@ -108,7 +108,7 @@ impl Alloc for Jemalloc {
         })
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
         if layout.size() != 0 {
@@ -120,7 +120,7 @@ impl Alloc for Jemalloc {
         }
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow(
         &self,
@@ -138,7 +138,7 @@ impl Alloc for Jemalloc {
         )
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn shrink(
         &self,
@@ -156,6 +156,22 @@ impl Alloc for Jemalloc {
         )
     }
 
+    /// Returns the usable size of the block at `ptr`, as reported by jemalloc.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        if layout.size() == 0 {
+            0
+        } else {
+            ffi::usable_size(ptr.as_ptr())
+        }
+    }
+
     /// Reallocate a block, growing or shrinking as needed.
     ///
     /// On grow, preserves existing contents up to `old_layout.size()`, and
@@ -172,7 +188,7 @@ impl Alloc for Jemalloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc(
         &self,
@@ -189,3 +205,13 @@ impl Alloc for Jemalloc {
         )
     }
 }
+
+impl Owns for Jemalloc {
+    const PRECISE: bool = false;
+
+    /// Always returns `true`, as jemalloc has no cheap way to tell which blocks it allocated.
+    #[inline]
+    fn owns(&self, _: NonNull<u8>, _: Layout) -> bool {
+        true
+    }
+}
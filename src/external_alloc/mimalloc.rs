@@ -2,7 +2,7 @@ use crate::{
     external_alloc::resize,
     ffi::mim as ffi,
     helpers::{null_q, zsl_check},
-    Alloc, AllocError,
+    Alloc, AllocError, Owns,
 };
 use core::{
     alloc::{GlobalAlloc, Layout},
@@ -16,32 +16,32 @@ use cty::c_void;
 pub struct MiMalloc;
 
 unsafe impl GlobalAlloc for MiMalloc {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         ffi::mi_malloc_aligned(layout.size(), layout.align()) as *mut u8
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         ffi::mi_free_size_aligned(ptr as *mut c_void, layout.size(), layout.align());
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
         ffi::mi_zalloc_aligned(layout.size(), layout.align()) as *mut u8
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
         ffi::mi_realloc_aligned(ptr as *mut c_void, new_size, layout.align()) as *mut u8
     }
 }
 
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 #[inline]
 fn zsl_check_alloc(
     layout: Layout,
@@ -56,19 +56,19 @@ fn zsl_check_alloc(
 }
 
 impl Alloc for MiMalloc {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         zsl_check_alloc(layout, ffi::mi_malloc_aligned)
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         zsl_check_alloc(layout, ffi::mi_zalloc_aligned)
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
         if layout.size() != 0 {
@@ -76,7 +76,7 @@ impl Alloc for MiMalloc {
         }
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow(
         &self,
@@ -100,7 +100,7 @@ impl Alloc for MiMalloc {
         )
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn shrink(
         &self,
@@ -124,7 +124,23 @@ impl Alloc for MiMalloc {
         )
     }
 
-    #[cfg_attr(miri, track_caller)]
+    /// Returns the usable size of the block at `ptr`, as reported by mimalloc.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        if layout.size() == 0 {
+            0
+        } else {
+            ffi::usable_size(ptr.as_ptr())
+        }
+    }
+
+    #[track_caller]
     #[inline]
     unsafe fn realloc(
         &self,
@@ -142,3 +158,16 @@ impl Alloc for MiMalloc {
         )
     }
 }
+
+impl Owns for MiMalloc {
+    const PRECISE: bool = false;
+
+    /// Returns whether `ptr` points into mimalloc's heap region.
+    ///
+    /// If mimalloc also backs other allocators in the process, e.g. when it is the global
+    /// allocator, their blocks are reported as owned too, so this isn't precise.
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, _: Layout) -> bool {
+        unsafe { ffi::mi_is_in_heap_region(ptr.as_ptr() as *const c_void) }
+    }
+}
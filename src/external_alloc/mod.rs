@@ -14,7 +14,7 @@ pub(crate) const REALLOC_DIFF_ALIGN: &str =
     "unsupported operation: attempted to reallocate with a different alignment";
 
 #[allow(dead_code)]
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 #[inline]
 pub(crate) unsafe fn resize<F: Fn() -> *mut c_void>(
     ralloc: F,
@@ -108,7 +108,7 @@ pub mod ffi {
             malloc_usable_size(ptr as *const c_void) as usize
         }
 
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         pub(crate) unsafe fn raw_ralloc(
             ptr: *mut c_void,
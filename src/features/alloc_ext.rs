@@ -1,7 +1,7 @@
 use crate::{
     error::AllocError,
     grow,
-    helpers::{alloc_write, AllocGuard},
+    helpers::{alloc_write, secure_zero, AllocGuard},
     ralloc,
     type_props::{PtrProps, SizedProps},
     Alloc, AllocPattern,
@@ -46,7 +46,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `T::SZ == 0`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_write<T>(&self, data: T) -> Result<NonNull<T>, AllocError> {
         alloc_write(self, data)
@@ -135,7 +135,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_filled(&self, layout: Layout, n: u8) -> Result<NonNull<u8>, AllocError> {
         match self.alloc(layout) {
@@ -183,7 +183,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator, be valid for reads
     ///   and writes, aligned, and a valid `T`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn drop_and_dealloc<T: ?Sized>(&self, ptr: NonNull<T>) {
         ptr.as_ptr().drop_in_place();
@@ -196,10 +196,10 @@ pub trait AllocExt: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn zero_and_dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
-        ptr.as_ptr().write_bytes(0, layout.size());
+        secure_zero(ptr.as_ptr(), layout.size());
         self.dealloc(ptr, layout);
     }
 
@@ -208,7 +208,7 @@ pub trait AllocExt: Alloc {
     /// # Safety
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc_typed<T: ?Sized>(&self, ptr: NonNull<T>) {
         self.dealloc(ptr.cast::<u8>(), ptr.layout());
@@ -219,10 +219,10 @@ pub trait AllocExt: Alloc {
     /// # Safety
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn zero_and_dealloc_typed<T: ?Sized>(&self, ptr: NonNull<T>) {
-        (ptr.as_ptr() as *mut u8).write_bytes(0, ptr.size());
+        secure_zero(ptr.as_ptr() as *mut u8, ptr.size());
         self.dealloc_typed(ptr);
     }
 
@@ -232,7 +232,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator, be valid for reads
     ///   and writes, aligned, and a valid `T`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn drop_zero_and_dealloc<T: ?Sized>(&self, ptr: NonNull<T>) {
         ptr.as_ptr().drop_in_place();
@@ -246,7 +246,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `data.size() == 0`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_copy_ref_to<T: ?Sized + crate::marker::UnsizedCopy>(
         &self,
@@ -266,7 +266,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `data.size() == 0`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_copy_ptr_to<T: ?Sized + crate::marker::UnsizedCopy>(
         &self,
@@ -287,7 +287,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `data.size() == 0`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_copy_ref_to_unchecked<T: ?Sized>(
         &self,
@@ -314,7 +314,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `data.size() == 0`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_copy_ptr_to_unchecked<T: ?Sized>(
         &self,
@@ -330,7 +330,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `T::SZ == 0`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_guard<T>(&'_ self) -> Result<AllocGuard<'_, T, Self>, AllocError> {
         match self.alloc(T::LAYOUT) {
@@ -377,7 +377,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_filled(
         &self,
@@ -431,7 +431,7 @@ pub trait AllocExt: Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_filled(
         &self,
@@ -3,7 +3,7 @@ use crate::{
     grow,
     helpers::{
         alloc_slice, dealloc_n, layout_or_sz_align, nonnull_slice_from_raw_parts,
-        nonnull_slice_len, slice_ptr_from_raw_parts, SliceAllocGuard, TRUNC_LGR,
+        nonnull_slice_len, secure_zero, slice_ptr_from_raw_parts, SliceAllocGuard, TRUNC_LGR,
     },
     ralloc, shrink,
     type_props::{PtrProps, SizedProps},
@@ -41,10 +41,10 @@ pub trait AllocSlice: Alloc {
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
     /// - [`AllocError::ZeroSizedLayout`] if the computed slice has a size of zero.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_slice<T>(&self, len: usize) -> Result<NonNull<[T]>, AllocError> {
-        alloc_slice(self, len, Self::alloc)
+        alloc_slice(self, len, false)
     }
 
     /// Attempts to allocate a zeroed block of memory for `len` instances of `T`.
@@ -54,10 +54,10 @@ pub trait AllocSlice: Alloc {
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
     /// - [`AllocError::ZeroSizedLayout`] if the computed slice has a size of zero.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_slice_zeroed<T>(&self, len: usize) -> Result<NonNull<[T]>, AllocError> {
-        alloc_slice(self, len, Self::alloc_zeroed)
+        alloc_slice(self, len, true)
     }
 
     /// Allocates uninitialized memory for a slice of `T` and clones each element from `data` into
@@ -89,7 +89,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if the slice is zero-sized.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_copy_slice_to<T: Copy>(&self, data: &[T]) -> Result<NonNull<[T]>, AllocError> {
         unsafe { self.alloc_copy_slice_to_unchecked(data) }
@@ -106,7 +106,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// The caller must ensure it is safe to copy the elements in `data`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_copy_slice_to_unchecked<T>(
         &self,
@@ -200,7 +200,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator, be valid for reads
     ///   and writes, aligned, a valid `[T]` for `init` elements, and a valid `[MaybeUninit<T>]`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn drop_and_dealloc_uninit_slice<T>(&self, ptr: NonNull<[MaybeUninit<T>]>, init: usize) {
         slice_ptr_from_raw_parts(ptr.as_ptr() as *mut T, init).drop_in_place();
@@ -214,7 +214,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator, be valid for reads
     ///   and writes, aligned, and a valid `T`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn drop_zero_and_dealloc_uninit_slice<T>(
         &self,
@@ -222,7 +222,7 @@ pub trait AllocSlice: Alloc {
         init: usize,
     ) {
         slice_ptr_from_raw_parts(slice.as_ptr() as *mut T, init).drop_in_place();
-        (slice.as_ptr() as *mut T).write_bytes(0, nonnull_slice_len(slice));
+        secure_zero(slice.as_ptr() as *mut T, nonnull_slice_len(slice));
         self.dealloc(slice.cast::<u8>(), slice.layout());
     }
 
@@ -234,7 +234,7 @@ pub trait AllocSlice: Alloc {
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
     /// - [`AllocError::ZeroSizedLayout`] if the computed slice has a size of zero.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_slice_guard<T>(
         &'_ self,
@@ -258,7 +258,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_slice<T>(
         &self,
@@ -285,7 +285,7 @@ pub trait AllocSlice: Alloc {
     /// - `len` must describe exactly the number of elements in that slice.
     // Safety #2 implies that `len` must be a valid length for the slice (which is required because
     // we use from_size_align_unchecked)
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_raw_slice<T>(
         &self,
@@ -308,7 +308,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_slice_zeroed<T>(
         &self,
@@ -333,7 +333,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_raw_slice_zeroed<T>(
         &self,
@@ -530,7 +530,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn shrink_slice<T>(
         &self,
@@ -555,7 +555,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn shrink_raw_slice<T>(
         &self,
@@ -579,7 +579,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn truncate_slice<T>(
         &self,
@@ -606,7 +606,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn truncate_raw_slice<T>(
         &self,
@@ -640,7 +640,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_slice<T>(
         &self,
@@ -667,7 +667,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_raw_slice<T>(
         &self,
@@ -693,7 +693,7 @@ pub trait AllocSlice: Alloc {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_slice_zeroed<T>(
         &self,
@@ -722,7 +722,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_raw_slice_zeroed<T>(
         &self,
@@ -927,7 +927,7 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `n` must be the exact number of `T` held in that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc_n<T>(&self, ptr: NonNull<T>, n: usize) {
         dealloc_n(self, ptr, n);
@@ -939,10 +939,10 @@ pub trait AllocSlice: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator, be valid for reads
     ///   and writes, aligned, and a valid `T`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn zero_and_dealloc_n<T>(&self, ptr: NonNull<T>, n: usize) {
-        ptr.as_ptr().write_bytes(0, n);
+        secure_zero(ptr.as_ptr(), n);
         self.dealloc_n(ptr, n);
     }
 
@@ -953,7 +953,7 @@ pub trait AllocSlice: Alloc {
     /// - `ptr` must point to a block of memory allocated using this allocator, be valid for reads
     ///   and writes, aligned, and contain `n` valid `T`.
     /// - `n` must be the exact number of `T` held in that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn drop_and_dealloc_n<T>(&self, ptr: NonNull<T>, n: usize) {
         slice_ptr_from_raw_parts(ptr.as_ptr(), n).drop_in_place();
@@ -975,7 +975,7 @@ pub trait AllocSliceExt: AllocSlice + crate::alloc_ext::AllocExt {
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
     /// - [`AllocError::ZeroSizedLayout`] if the computed slice has a size of zero.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_slice_filled<T>(&self, len: usize, n: u8) -> Result<NonNull<[T]>, AllocError> {
         let layout = layout_or_sz_align::<T>(len)
@@ -1016,7 +1016,7 @@ pub trait AllocSliceExt: AllocSlice + crate::alloc_ext::AllocExt {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_slice_filled<T>(
         &self,
@@ -1042,7 +1042,7 @@ pub trait AllocSliceExt: AllocSlice + crate::alloc_ext::AllocExt {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_raw_slice_filled<T>(
         &self,
@@ -1122,7 +1122,7 @@ pub trait AllocSliceExt: AllocSlice + crate::alloc_ext::AllocExt {
     /// # Safety
     ///
     /// - `slice` must point to a slice allocated using this allocator.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_slice_filled<T>(
         &self,
@@ -1148,7 +1148,7 @@ pub trait AllocSliceExt: AllocSlice + crate::alloc_ext::AllocExt {
     ///
     /// - `ptr` must point to a slice allocated using this allocator.
     /// - `len` must describe exactly the number of elements in that slice.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_raw_slice_filled<T>(
         &self,
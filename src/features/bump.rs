@@ -0,0 +1,541 @@
+use crate::{
+    error::{AllocError, ArithOp},
+    helpers::zsl_check,
+    Alloc, AllocPattern, DefaultAlloc, Owns,
+};
+use core::{
+    alloc::Layout,
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+    mem::{align_of, size_of},
+    ptr::NonNull,
+};
+
+/// The default size of the chunks a [`BumpAlloc`] requests from its backing allocator.
+pub const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+const BAD_CHECKPOINT: &str = "checkpoint does not belong to this allocator's current state";
+
+/// The header at the start of every chunk.
+struct ChunkHeader {
+    /// The previously allocated chunk.
+    prev: Option<NonNull<ChunkHeader>>,
+    /// The size of the chunk in bytes, including this header.
+    size: usize,
+    /// A unique id for the chunk, used to validate checkpoints.
+    id: usize,
+}
+
+const HEADER_SZ: usize = size_of::<ChunkHeader>();
+const HEADER_ALIGN: usize = align_of::<ChunkHeader>();
+
+/// A bump allocator which carves allocations out of chunks obtained from `A`.
+///
+/// Deallocation is a no-op, except that deallocating the most recent allocation rolls the bump
+/// pointer back. The most recent allocation can also be grown and shrunk in place. All memory is
+/// returned to `A` when the allocator is dropped, and can be reclaimed earlier using
+/// [`reset`](BumpAlloc::reset) or [`rewind`](BumpAlloc::rewind).
+///
+/// This allocator uses interior mutability and isn't [`Sync`]. Share it by reference, e.g. by
+/// using `&BumpAlloc<A>` as the allocator of an [`OwnedBuf`](crate::owned::OwnedBuf).
+pub struct BumpAlloc<A: Alloc = DefaultAlloc> {
+    /// The backing allocator.
+    alloc: A,
+    /// The minimum size of new chunks.
+    chunk_size: usize,
+    /// The current chunk.
+    cur: Cell<Option<NonNull<ChunkHeader>>>,
+    /// The offset of the next free byte in the current chunk.
+    pos: Cell<usize>,
+    /// The offset of the most recent allocation in the current chunk.
+    last: Cell<usize>,
+    /// The id the next chunk will get.
+    next_id: Cell<usize>,
+}
+
+/// A saved position of a [`BumpAlloc`], which it can be [rewound](BumpAlloc::rewind) to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The id of the chunk which was current, or `0` if there was none.
+    chunk: usize,
+    /// The offset of the next free byte in that chunk.
+    pos: usize,
+}
+
+impl BumpAlloc {
+    /// Creates a new, empty bump allocator backed by the default allocator.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> BumpAlloc {
+        BumpAlloc::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> BumpAlloc<A> {
+    /// Creates a new, empty bump allocator backed by `alloc`.
+    ///
+    /// No memory is allocated until the first allocation.
+    #[inline]
+    pub const fn new_in(alloc: A) -> BumpAlloc<A> {
+        BumpAlloc::with_chunk_size_in(DEFAULT_CHUNK_SIZE, alloc)
+    }
+
+    /// Creates a new, empty bump allocator backed by `alloc`, which requests chunks of at least
+    /// `chunk_size` bytes.
+    #[inline]
+    pub const fn with_chunk_size_in(chunk_size: usize, alloc: A) -> BumpAlloc<A> {
+        BumpAlloc {
+            alloc,
+            chunk_size,
+            cur: Cell::new(None),
+            pos: Cell::new(0),
+            last: Cell::new(0),
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Gets a reference to the backing allocator.
+    #[inline]
+    pub const fn backing(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the minimum size of new chunks.
+    #[inline]
+    pub const fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the total size in bytes of all chunks currently held, including their headers.
+    #[must_use]
+    pub fn allocated_bytes(&self) -> usize {
+        let mut total = 0;
+        let mut chunk = self.cur.get();
+        while let Some(c) = chunk {
+            unsafe {
+                total += (*c.as_ptr()).size;
+                chunk = (*c.as_ptr()).prev;
+            }
+        }
+        total
+    }
+
+    /// Saves the current position, so allocations made after this can be freed at once using
+    /// [`rewind`](BumpAlloc::rewind).
+    #[must_use]
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            chunk: match self.cur.get() {
+                Some(c) => unsafe { (*c.as_ptr()).id },
+                None => 0,
+            },
+            pos: self.pos.get(),
+        }
+    }
+
+    /// Frees every allocation made after `checkpoint` was taken, returning chunks which are no
+    /// longer needed to the backing allocator.
+    ///
+    /// Taking `&mut self` guarantees no borrowing users of the allocator are alive, but any raw
+    /// pointers to memory allocated after the checkpoint become dangling.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::Other`] if `checkpoint` wasn't taken from this allocator, or was
+    ///   invalidated by rewinding past it or resetting.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) -> Result<(), AllocError> {
+        // make sure the checkpoint is valid before freeing anything.
+        let mut chunk = self.cur.get();
+        loop {
+            match chunk {
+                Some(c) if unsafe { (*c.as_ptr()).id } == checkpoint.chunk => {
+                    if chunk == self.cur.get() && checkpoint.pos > self.pos.get() {
+                        return Err(AllocError::Other(BAD_CHECKPOINT));
+                    }
+                    break;
+                }
+                Some(c) => chunk = unsafe { (*c.as_ptr()).prev },
+                None if checkpoint.chunk == 0 => break,
+                None => return Err(AllocError::Other(BAD_CHECKPOINT)),
+            }
+        }
+
+        while self.cur.get() != chunk {
+            unsafe {
+                self.pop_chunk();
+            }
+        }
+        self.pos
+            .set(if chunk.is_some() { checkpoint.pos } else { 0 });
+        self.last.set(self.pos.get());
+        Ok(())
+    }
+
+    /// Frees every allocation, keeping only the most recent chunk for reuse.
+    ///
+    /// Any raw pointers to memory allocated using this allocator become dangling, and all
+    /// existing checkpoints are invalidated.
+    pub fn reset(&mut self) {
+        if let Some(cur) = self.cur.get() {
+            unsafe {
+                while let Some(prev) = (*cur.as_ptr()).prev {
+                    (*cur.as_ptr()).prev = (*prev.as_ptr()).prev;
+                    self.dealloc_chunk(prev);
+                }
+                (*cur.as_ptr()).id = self.take_id();
+            }
+            self.pos.set(HEADER_SZ);
+            self.last.set(HEADER_SZ);
+        }
+    }
+
+    #[inline]
+    fn take_id(&self) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    /// Removes and deallocates the current chunk.
+    ///
+    /// # Safety
+    ///
+    /// There must be a current chunk.
+    unsafe fn pop_chunk(&self) {
+        let cur = match self.cur.get() {
+            Some(c) => c,
+            None => core::hint::unreachable_unchecked(),
+        };
+        self.cur.set((*cur.as_ptr()).prev);
+        self.pos
+            .set(self.cur.get().map_or(0, |c| (*c.as_ptr()).size));
+        self.last.set(self.pos.get());
+        self.dealloc_chunk(cur);
+    }
+
+    #[inline]
+    unsafe fn dealloc_chunk(&self, chunk: NonNull<ChunkHeader>) {
+        let size = (*chunk.as_ptr()).size;
+        self.alloc.dealloc(
+            chunk.cast(),
+            Layout::from_size_align_unchecked(size, HEADER_ALIGN),
+        );
+    }
+
+    /// Attempts to bump-allocate `layout` from the current chunk.
+    fn bump(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let chunk = self.cur.get()?;
+        let base = chunk.as_ptr() as usize;
+        let addr = (base + self.pos.get()).checked_add(layout.align() - 1)? & !(layout.align() - 1);
+        let start = addr - base;
+        let end = start.checked_add(layout.size())?;
+        if end > unsafe { (*chunk.as_ptr()).size } {
+            return None;
+        }
+        self.last.set(start);
+        self.pos.set(end);
+        Some(unsafe { NonNull::new_unchecked(chunk.cast::<u8>().as_ptr().add(start)) })
+    }
+
+    /// Allocates a new chunk big enough for `layout` and makes it current.
+    fn push_chunk(&self, layout: Layout) -> Result<(), AllocError> {
+        let with_header = match HEADER_SZ.checked_add(layout.size()) {
+            Some(n) => n,
+            None => {
+                return Err(AllocError::ArithmeticOverflow(
+                    HEADER_SZ,
+                    ArithOp::Add,
+                    layout.size(),
+                ))
+            }
+        };
+        let needed = match with_header.checked_add(layout.align()) {
+            Some(n) => n,
+            None => {
+                return Err(AllocError::ArithmeticOverflow(
+                    with_header,
+                    ArithOp::Add,
+                    layout.align(),
+                ))
+            }
+        };
+        let size = if needed > self.chunk_size {
+            needed
+        } else {
+            self.chunk_size
+        };
+        let chunk_layout = Layout::from_size_align(size, HEADER_ALIGN)
+            .map_err(|_| AllocError::LayoutError(size, HEADER_ALIGN))?;
+        let chunk = self.alloc.alloc(chunk_layout)?.cast::<ChunkHeader>();
+        unsafe {
+            chunk.as_ptr().write(ChunkHeader {
+                prev: self.cur.get(),
+                size,
+                id: self.take_id(),
+            });
+        }
+        self.cur.set(Some(chunk));
+        self.pos.set(HEADER_SZ);
+        self.last.set(HEADER_SZ);
+        Ok(())
+    }
+
+    /// Returns whether the block at `ptr` of `size` bytes is the most recent allocation.
+    #[inline]
+    fn is_last(&self, ptr: NonNull<u8>, size: usize) -> bool {
+        match self.cur.get() {
+            Some(c) => {
+                ptr.as_ptr() as usize == c.as_ptr() as usize + self.last.get()
+                    && self.last.get() + size == self.pos.get()
+            }
+            None => false,
+        }
+    }
+
+    /// Attempts to resize the block at `ptr` in place, which is only possible for the most recent
+    /// allocation.
+    fn resize_last(&self, ptr: NonNull<u8>, old_size: usize, new_size: usize) -> bool {
+        if !self.is_last(ptr, old_size) {
+            return false;
+        }
+        let end = match self.last.get().checked_add(new_size) {
+            Some(end) => end,
+            None => return false,
+        };
+        match self.cur.get() {
+            Some(c) if end <= unsafe { (*c.as_ptr()).size } => {
+                self.pos.set(end);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[inline]
+    unsafe fn grow_inner(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zero: bool,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size()
+            && ptr.as_ptr() as usize % new_layout.align() == 0
+            && self.resize_last(ptr, old_layout.size(), new_layout.size())
+        {
+            if zero {
+                ptr.as_ptr()
+                    .add(old_layout.size())
+                    .write_bytes(0, new_layout.size() - old_layout.size());
+            }
+            return Ok(ptr);
+        }
+        if zero {
+            crate::grow(
+                self,
+                ptr,
+                old_layout,
+                new_layout,
+                AllocPattern::<fn(usize) -> u8>::Zero,
+            )
+        } else {
+            crate::grow(
+                self,
+                ptr,
+                old_layout,
+                new_layout,
+                AllocPattern::<fn(usize) -> u8>::None,
+            )
+        }
+    }
+}
+
+impl<A: Alloc> Alloc for BumpAlloc<A> {
+    #[track_caller]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        zsl_check(layout, |layout| {
+            if let Some(ptr) = self.bump(layout) {
+                return Ok(ptr);
+            }
+            self.push_chunk(layout)?;
+            // the new chunk always fits the layout.
+            self.bump(layout).ok_or(AllocError::AllocFailed(layout))
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        if self.is_last(ptr, layout.size()) {
+            self.pos.set(self.last.get());
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.grow_inner(ptr, old_layout, new_layout, false)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.grow_inner(ptr, old_layout, new_layout, true)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() != 0
+            && new_layout.size() <= old_layout.size()
+            && ptr.as_ptr() as usize % new_layout.align() == 0
+        {
+            // any block can shrink in place, but only the most recent one gives memory back.
+            let _ = self.resize_last(ptr, old_layout.size(), new_layout.size());
+            return Ok(ptr);
+        }
+        crate::shrink(self, ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size() {
+            self.grow(ptr, old_layout, new_layout)
+        } else {
+            self.shrink(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size() {
+            self.grow_zeroed(ptr, old_layout, new_layout)
+        } else {
+            self.shrink(ptr, old_layout, new_layout)
+        }
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: Alloc> crate::in_place::ResizeInPlace for BumpAlloc<A> {
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if new_size == 0 {
+            Err(AllocError::Other(crate::in_place::RESIZE_IP_ZS))
+        } else if new_size < old_layout.size() {
+            Err(AllocError::GrowSmallerNewLayout(
+                old_layout.size(),
+                new_size,
+            ))
+        } else if self.resize_last(ptr, old_layout.size(), new_size) {
+            Ok(())
+        } else {
+            Err(AllocError::Other(crate::in_place::CANNOT_RESIZE_IP))
+        }
+    }
+
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if new_size == 0 {
+            Err(AllocError::Other(crate::in_place::RESIZE_IP_ZS))
+        } else if new_size > old_layout.size() {
+            Err(AllocError::ShrinkBiggerNewLayout(
+                old_layout.size(),
+                new_size,
+            ))
+        } else {
+            let _ = self.resize_last(ptr, old_layout.size(), new_size);
+            Ok(())
+        }
+    }
+}
+
+impl<A: Alloc> Owns for BumpAlloc<A> {
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        let start = ptr.as_ptr() as usize;
+        let end = match start.checked_add(layout.size()) {
+            Some(end) => end,
+            None => return false,
+        };
+        let mut chunk = self.cur.get();
+        while let Some(c) = chunk {
+            let base = c.as_ptr() as usize;
+            unsafe {
+                if start >= base + HEADER_SZ && end <= base + (*c.as_ptr()).size {
+                    return true;
+                }
+                chunk = (*c.as_ptr()).prev;
+            }
+        }
+        false
+    }
+}
+
+impl<A: Alloc + Default> Default for BumpAlloc<A> {
+    #[inline]
+    fn default() -> BumpAlloc<A> {
+        BumpAlloc::new_in(A::default())
+    }
+}
+
+impl<A: Alloc + Debug> Debug for BumpAlloc<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BumpAlloc")
+            .field("alloc", &self.alloc)
+            .field("chunk_size", &self.chunk_size)
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Alloc> Drop for BumpAlloc<A> {
+    fn drop(&mut self) {
+        while self.cur.get().is_some() {
+            unsafe {
+                self.pop_chunk();
+            }
+        }
+    }
+}
+
+// SAFETY: the chunks are owned by the allocator, so moving it to another thread is as safe as
+//  moving `A`. it isn't `Sync` because of the `Cell`s.
+unsafe impl<A: Alloc + Send> Send for BumpAlloc<A> {}
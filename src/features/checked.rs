@@ -0,0 +1,475 @@
+use crate::{
+    error::{AllocError, PanicOnViolation, Violation, ViolationHandler, ViolationKind},
+    spin::SpinLock,
+    Alloc, DefaultAlloc, Owns,
+};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug, Formatter},
+    ptr::NonNull,
+};
+
+/// The default number of blocks a [`Checked`] allocator can keep track of.
+pub const TABLE_SLOTS: usize = 512;
+
+const UNKNOWN_BLOCK: &str = "pointer does not refer to a live block";
+const TABLE_FULL: &str = "the block table is full";
+
+/// What is known about a block.
+#[derive(Clone, Copy)]
+struct Record {
+    layout: Layout,
+    live: bool,
+}
+
+/// The records of up to `N` blocks, keyed by address.
+///
+/// Live blocks take priority over records of deallocated ones, which are reused first.
+struct Table<const N: usize>([Option<(usize, Record)>; N]);
+
+impl<const N: usize> Table<N> {
+    const fn new() -> Table<N> {
+        Table([None; N])
+    }
+
+    fn get(&self, addr: usize) -> Option<Record> {
+        self.0.iter().find_map(|slot| match slot {
+            Some((a, record)) if *a == addr => Some(*record),
+            _ => None,
+        })
+    }
+
+    fn insert(&mut self, addr: usize, record: Record) -> Result<(), AllocError> {
+        let existing = self
+            .0
+            .iter()
+            .position(|slot| matches!(slot, Some((a, _)) if *a == addr));
+        let slot = existing
+            .or_else(|| self.0.iter().position(Option::is_none))
+            .or_else(|| {
+                self.0
+                    .iter()
+                    .position(|slot| matches!(slot, Some((_, r)) if !r.live))
+            });
+        match slot {
+            Some(i) => {
+                self.0[i] = Some((addr, record));
+                Ok(())
+            }
+            None => Err(AllocError::Other(TABLE_FULL)),
+        }
+    }
+
+    fn live(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|slot| matches!(slot, Some((_, r)) if r.live))
+            .count()
+    }
+}
+
+/// An allocator which records the layout of every live block and checks the pointers and layouts
+/// passed to `dealloc`, `grow`, `shrink` and `realloc` against it.
+///
+/// Misuse is reported to `H` as a [`Violation`]:
+///
+/// - [`ViolationKind::LayoutMismatch`] if the layout doesn't describe the block. Like the inner
+///   allocator, any size between the recorded one and the [usable size](Alloc::usable_size) with
+///   the recorded alignment is accepted. The operation then continues using the recorded layout.
+/// - [`ViolationKind::DoubleFree`] if the block was already deallocated.
+/// - [`ViolationKind::UnknownPointer`] if the pointer was never returned by this allocator.
+///
+/// In the last two cases, the block is left alone, and resizing fails with
+/// [`AllocError::Other`].
+///
+/// Blocks are recorded in a fixed table of `SLOTS` entries, [`TABLE_SLOTS`] by default, which can
+/// be changed using [`with_slots`](Checked::with_slots). Records of deallocated blocks are reused
+/// first, and allocations fail with [`AllocError::Other`] once the table is full of live blocks.
+pub struct Checked<
+    A: Alloc = DefaultAlloc,
+    H: ViolationHandler = PanicOnViolation,
+    const SLOTS: usize = TABLE_SLOTS,
+> {
+    /// The inner allocator.
+    alloc: A,
+    /// The violation handler.
+    handler: H,
+    /// The records of all blocks.
+    table: SpinLock<Table<SLOTS>>,
+}
+
+impl Checked {
+    /// Creates a new checked allocator backed by the default allocator, which panics on
+    /// violations.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Checked {
+        Checked::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> Checked<A> {
+    /// Creates a new checked allocator backed by `alloc`, which panics on violations.
+    #[inline]
+    pub fn new_in(alloc: A) -> Checked<A> {
+        Checked::with_handler_in(PanicOnViolation, alloc)
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler> Checked<A, H> {
+    /// Creates a new checked allocator backed by `alloc`, which reports violations to `handler`.
+    #[inline]
+    pub fn with_handler_in(handler: H, alloc: A) -> Checked<A, H> {
+        Checked {
+            alloc,
+            handler,
+            table: SpinLock::new(Table::new()),
+        }
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler, const SLOTS: usize> Checked<A, H, SLOTS> {
+    /// Changes the table to hold up to `N` blocks, forgetting the deallocated ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any blocks are live.
+    #[must_use]
+    pub fn with_slots<const N: usize>(self) -> Checked<A, H, N> {
+        assert_eq!(self.live(), 0, "blocks are still live");
+        Checked {
+            alloc: self.alloc,
+            handler: self.handler,
+            table: SpinLock::new(Table::new()),
+        }
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Gets a reference to the violation handler.
+    #[inline]
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Returns the number of live blocks.
+    pub fn live(&self) -> usize {
+        self.table.lock().live()
+    }
+
+    /// Returns the layout the live block at `ptr` was allocated with, if any.
+    pub fn layout_of(&self, ptr: NonNull<u8>) -> Option<Layout> {
+        match self.table.lock().get(ptr.as_ptr() as usize) {
+            Some(record) if record.live => Some(record.layout),
+            _ => None,
+        }
+    }
+
+    /// Returns whether `layout` describes the live block at `ptr` which was recorded with
+    /// `recorded`.
+    fn describes(&self, ptr: NonNull<u8>, recorded: Layout, layout: Layout) -> bool {
+        layout == recorded
+            || (layout.align() == recorded.align()
+                && layout.size() > recorded.size()
+                // SAFETY: the block is live and `recorded` describes it exactly.
+                && layout.size() <= unsafe { self.alloc.usable_size(ptr, recorded) })
+    }
+
+    /// Records a new live block, deallocating it again if it can't be recorded.
+    #[track_caller]
+    fn record(
+        &self,
+        res: Result<NonNull<u8>, AllocError>,
+        layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let ptr = res?;
+        let record = Record { layout, live: true };
+        if let Err(e) = self.table.lock().insert(ptr.as_ptr() as usize, record) {
+            unsafe {
+                self.alloc.dealloc(ptr, layout);
+            }
+            return Err(e);
+        }
+        Ok(ptr)
+    }
+
+    /// Checks `ptr` and `layout` against the recorded block, reporting any misuse, and returns the
+    /// recorded layout if the block is live.
+    ///
+    /// If `free` is true, the block is marked as deallocated.
+    #[track_caller]
+    fn verify(&self, ptr: NonNull<u8>, layout: Layout, free: bool) -> Option<Layout> {
+        let addr = ptr.as_ptr() as usize;
+        let mut table = self.table.lock();
+        let kind = match table.get(addr) {
+            Some(record) if record.live => {
+                if free {
+                    // the record is replaced in place, so this can't fail.
+                    let _ = table.insert(
+                        addr,
+                        Record {
+                            layout: record.layout,
+                            live: false,
+                        },
+                    );
+                }
+                drop(table);
+                if self.describes(ptr, record.layout, layout) {
+                    return Some(layout);
+                }
+                self.report(ViolationKind::LayoutMismatch(record.layout), ptr, layout);
+                return Some(record.layout);
+            }
+            Some(_) => ViolationKind::DoubleFree,
+            None => ViolationKind::UnknownPointer,
+        };
+        drop(table);
+        self.report(kind, ptr, layout);
+        None
+    }
+
+    #[track_caller]
+    #[inline]
+    fn report(&self, kind: ViolationKind, ptr: NonNull<u8>, layout: Layout) {
+        self.handler.report(&Violation { kind, ptr, layout });
+    }
+
+    /// Checks the block at `ptr`, resizes it using `f` and records the result.
+    #[track_caller]
+    unsafe fn resize<F: FnOnce(NonNull<u8>, Layout) -> Result<NonNull<u8>, AllocError>>(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        f: F,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let old_layout = match self.verify(ptr, old_layout, false) {
+            Some(layout) => layout,
+            None => return Err(AllocError::Other(UNKNOWN_BLOCK)),
+        };
+        let new_ptr = f(ptr, old_layout)?;
+        let mut table = self.table.lock();
+        if new_ptr != ptr {
+            let _ = table.insert(
+                ptr.as_ptr() as usize,
+                Record {
+                    layout: old_layout,
+                    live: false,
+                },
+            );
+        }
+        let recorded = table.insert(
+            new_ptr.as_ptr() as usize,
+            Record {
+                layout: new_layout,
+                live: true,
+            },
+        );
+        drop(table);
+        if let Err(e) = recorded {
+            self.alloc.dealloc(new_ptr, new_layout);
+            return Err(e);
+        }
+        Ok(new_ptr)
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler, const SLOTS: usize> Alloc for Checked<A, H, SLOTS> {
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.record(self.alloc.alloc(layout), layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.record(self.alloc.alloc_zeroed(layout), layout)
+    }
+
+    /// Checks the block at `ptr`, then deallocates it using the layout it was allocated with.
+    ///
+    /// Unknown and already deallocated blocks are reported and left alone.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 && self.layout_of(ptr).is_none() {
+            // zero-sized blocks may be dangling pointers which were never recorded.
+            self.alloc.dealloc(ptr, layout);
+        } else if let Some(layout) = self.verify(ptr, layout, true) {
+            self.alloc.dealloc(ptr, layout);
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, |ptr, old_layout| {
+            self.alloc.grow(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, |ptr, old_layout| {
+            self.alloc.grow_zeroed(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, |ptr, old_layout| {
+            self.alloc.shrink(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, |ptr, old_layout| {
+            self.alloc.realloc(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, |ptr, old_layout| {
+            self.alloc.realloc_zeroed(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        self.alloc.usable_size(ptr, layout)
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler, const SLOTS: usize> Owns for Checked<A, H, SLOTS> {
+    /// Returns whether `ptr` is a live block from this allocator which `layout` describes.
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        match self.layout_of(ptr) {
+            Some(recorded) => self.describes(ptr, recorded, layout),
+            None => false,
+        }
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, H: ViolationHandler, const SLOTS: usize>
+    crate::in_place::ResizeInPlace for Checked<A, H, SLOTS>
+{
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.resize_in_place(ptr, old_layout, new_size, A::grow_in_place)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.resize_in_place(ptr, old_layout, new_size, A::shrink_in_place)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, H: ViolationHandler, const SLOTS: usize>
+    Checked<A, H, SLOTS>
+{
+    /// Checks the block at `ptr`, resizes it in place using `f` and records its new size.
+    #[track_caller]
+    unsafe fn resize_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        f: unsafe fn(&A, NonNull<u8>, Layout, usize) -> Result<(), AllocError>,
+    ) -> Result<(), AllocError> {
+        let old_layout = match self.verify(ptr, old_layout, false) {
+            Some(layout) => layout,
+            None => return Err(AllocError::Other(UNKNOWN_BLOCK)),
+        };
+        let new_layout = Layout::from_size_align(new_size, old_layout.align())
+            .map_err(|_| AllocError::LayoutError(new_size, old_layout.align()))?;
+        f(&self.alloc, ptr, old_layout, new_size)?;
+        // the record is replaced in place, so this can't fail.
+        let _ = self.table.lock().insert(
+            ptr.as_ptr() as usize,
+            Record {
+                layout: new_layout,
+                live: true,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl<A: Alloc + Default, H: ViolationHandler + Default, const SLOTS: usize> Default
+    for Checked<A, H, SLOTS>
+{
+    #[inline]
+    fn default() -> Checked<A, H, SLOTS> {
+        Checked {
+            alloc: A::default(),
+            handler: H::default(),
+            table: SpinLock::new(Table::new()),
+        }
+    }
+}
+
+impl<A: Alloc + Debug, H: ViolationHandler, const SLOTS: usize> Debug for Checked<A, H, SLOTS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checked")
+            .field("alloc", &self.alloc)
+            .field("live", &self.live())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,423 @@
+use crate::{error::AllocError, Alloc, DefaultAlloc, Owns};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug, Formatter},
+    ops::{BitOr, BitOrAssign},
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicU32, AtomicU8, AtomicUsize,
+        Ordering::{Relaxed, SeqCst},
+    },
+};
+
+/// A set of allocator operations which a [`FailingAlloc`] may fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FailOps(u8);
+
+impl FailOps {
+    /// No operations.
+    pub const NONE: FailOps = FailOps(0);
+    /// [`alloc`](Alloc::alloc) and [`alloc_zeroed`](Alloc::alloc_zeroed).
+    pub const ALLOC: FailOps = FailOps(1);
+    /// [`grow`](Alloc::grow), [`grow_zeroed`](Alloc::grow_zeroed), and growing
+    /// [`realloc`](Alloc::realloc)s.
+    pub const GROW: FailOps = FailOps(1 << 1);
+    /// [`shrink`](Alloc::shrink) and shrinking [`realloc`](Alloc::realloc)s.
+    pub const SHRINK: FailOps = FailOps(1 << 2);
+    /// All operations.
+    pub const ALL: FailOps = FailOps(0b111);
+
+    /// Returns whether all operations in `other` are in this set.
+    #[must_use]
+    #[inline]
+    pub const fn contains(self, other: FailOps) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for FailOps {
+    #[inline]
+    fn default() -> FailOps {
+        FailOps::ALL
+    }
+}
+
+impl BitOr for FailOps {
+    type Output = FailOps;
+
+    #[inline]
+    fn bitor(self, rhs: FailOps) -> FailOps {
+        FailOps(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for FailOps {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: FailOps) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// An allocator which injects [`AllocError::AllocFailed`] failures into the operations of `A`, for
+/// testing out-of-memory handling.
+///
+/// Only calls of the [selected operations](FailingAlloc::only) are counted and may fail. A call
+/// fails if any of the configured rules match:
+///
+/// - it is the [Nth call](FailingAlloc::fail_nth),
+/// - it is a multiple of [every Nth call](FailingAlloc::fail_every),
+/// - its new layout is [above a size threshold](FailingAlloc::fail_above), or
+/// - the built-in PRNG [decides so](FailingAlloc::fail_fraction).
+///
+/// No rules are configured by default, so nothing fails. Failing calls never reach `A`, leaving
+/// any existing block untouched.
+pub struct FailingAlloc<A: Alloc = DefaultAlloc> {
+    /// The inner allocator.
+    alloc: A,
+    /// The operations which may fail.
+    ops: AtomicU8,
+    /// The 1-based index of the call to fail, or `0`.
+    nth: AtomicUsize,
+    /// The interval of calls to fail, or `0`.
+    every: AtomicUsize,
+    /// The size above which calls fail.
+    above: AtomicUsize,
+    /// The probability of a call failing, in units of 2<sup>-32</sup>.
+    rate: AtomicU32,
+    /// The state of the PRNG.
+    rng: AtomicU32,
+    /// The number of counted calls.
+    calls: AtomicUsize,
+    /// The number of injected failures.
+    failures: AtomicUsize,
+}
+
+impl FailingAlloc {
+    /// Creates a new failing allocator backed by the default allocator.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> FailingAlloc {
+        FailingAlloc::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> FailingAlloc<A> {
+    /// Creates a new failing allocator backed by `alloc`, with no failure rules.
+    #[inline]
+    pub const fn new_in(alloc: A) -> FailingAlloc<A> {
+        FailingAlloc {
+            alloc,
+            ops: AtomicU8::new(FailOps::ALL.0),
+            nth: AtomicUsize::new(0),
+            every: AtomicUsize::new(0),
+            above: AtomicUsize::new(usize::MAX),
+            rate: AtomicU32::new(0),
+            rng: AtomicU32::new(1),
+            calls: AtomicUsize::new(0),
+            failures: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Only counts and fails calls of `ops`.
+    #[must_use]
+    #[inline]
+    pub fn only(self, ops: FailOps) -> FailingAlloc<A> {
+        self.set_only(ops);
+        self
+    }
+
+    /// Fails the `n`th counted call, starting at `1`. `0` disables this rule.
+    #[must_use]
+    #[inline]
+    pub fn fail_nth(self, n: usize) -> FailingAlloc<A> {
+        self.set_fail_nth(n);
+        self
+    }
+
+    /// Fails every `n`th counted call. `0` disables this rule.
+    #[must_use]
+    #[inline]
+    pub fn fail_every(self, n: usize) -> FailingAlloc<A> {
+        self.set_fail_every(n);
+        self
+    }
+
+    /// Fails calls whose new layout is bigger than `size` bytes. `usize::MAX` disables this rule.
+    #[must_use]
+    #[inline]
+    pub fn fail_above(self, size: usize) -> FailingAlloc<A> {
+        self.set_fail_above(size);
+        self
+    }
+
+    /// Fails about `numerator / denominator` of the counted calls, as decided by a PRNG seeded
+    /// with `seed`. The same seed always fails the same calls.
+    #[must_use]
+    #[inline]
+    pub fn fail_fraction(self, numerator: u32, denominator: u32, seed: u32) -> FailingAlloc<A> {
+        self.set_fail_fraction(numerator, denominator, seed);
+        self
+    }
+
+    /// Sets which operations are counted and may fail.
+    #[inline]
+    pub fn set_only(&self, ops: FailOps) {
+        self.ops.store(ops.0, SeqCst);
+    }
+
+    /// Sets the 1-based index of the counted call to fail. `0` disables this rule.
+    #[inline]
+    pub fn set_fail_nth(&self, n: usize) {
+        self.nth.store(n, SeqCst);
+    }
+
+    /// Sets the interval of counted calls to fail. `0` disables this rule.
+    #[inline]
+    pub fn set_fail_every(&self, n: usize) {
+        self.every.store(n, SeqCst);
+    }
+
+    /// Sets the size above which calls fail. `usize::MAX` disables this rule.
+    #[inline]
+    pub fn set_fail_above(&self, size: usize) {
+        self.above.store(size, SeqCst);
+    }
+
+    /// Sets the fraction of counted calls to fail, and reseeds the PRNG. A `numerator` of `0`
+    /// disables this rule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero.
+    #[inline]
+    pub fn set_fail_fraction(&self, numerator: u32, denominator: u32, seed: u32) {
+        assert!(denominator != 0, "denominator must be non-zero");
+        let rate = (u64::from(numerator) << 32) / u64::from(denominator);
+        #[allow(clippy::cast_possible_truncation)]
+        self.rate.store(
+            if rate > u64::from(u32::MAX) {
+                u32::MAX
+            } else {
+                rate as u32
+            },
+            SeqCst,
+        );
+        // xorshift gets stuck at zero.
+        self.rng.store(if seed == 0 { 1 } else { seed }, SeqCst);
+    }
+
+    /// Returns the number of calls counted so far.
+    #[inline]
+    pub fn calls(&self) -> usize {
+        self.calls.load(SeqCst)
+    }
+
+    /// Returns the number of failures injected so far.
+    #[inline]
+    pub fn failures(&self) -> usize {
+        self.failures.load(SeqCst)
+    }
+
+    /// Resets the call and failure counts, so [`fail_nth`](FailingAlloc::fail_nth) applies again.
+    #[inline]
+    pub fn reset_counts(&self) {
+        self.calls.store(0, SeqCst);
+        self.failures.store(0, SeqCst);
+    }
+
+    /// Advances the PRNG, returning the next value.
+    fn next_rand(&self) -> u32 {
+        let step = |mut x: u32| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        match self.rng.fetch_update(Relaxed, Relaxed, |x| Some(step(x))) {
+            Ok(prev) | Err(prev) => step(prev),
+        }
+    }
+
+    /// Counts a call of `op` with a new layout of `layout` and decides whether it fails.
+    fn should_fail(&self, op: FailOps, layout: Layout) -> bool {
+        if !FailOps(self.ops.load(SeqCst)).contains(op) {
+            return false;
+        }
+        let call = self.calls.fetch_add(1, SeqCst) + 1;
+        let nth = self.nth.load(SeqCst);
+        let every = self.every.load(SeqCst);
+        let rate = self.rate.load(SeqCst);
+
+        // always advance the PRNG so the outcome doesn't depend on the other rules.
+        let rand = if rate == 0 {
+            None
+        } else {
+            Some(self.next_rand())
+        };
+
+        let fail = (nth != 0 && call == nth)
+            || (every != 0 && call % every == 0)
+            || layout.size() > self.above.load(SeqCst)
+            || rand.map_or(false, |r| r < rate);
+        if fail {
+            self.failures.fetch_add(1, SeqCst);
+        }
+        fail
+    }
+
+    /// Runs `f` unless the call should fail.
+    #[track_caller]
+    #[inline]
+    fn check<F: FnOnce() -> Result<NonNull<u8>, AllocError>>(
+        &self,
+        op: FailOps,
+        layout: Layout,
+        f: F,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if self.should_fail(op, layout) {
+            Err(AllocError::AllocFailed(layout))
+        } else {
+            f()
+        }
+    }
+
+    #[inline]
+    fn resize_op(old_layout: Layout, new_layout: Layout) -> FailOps {
+        if new_layout.size() < old_layout.size() {
+            FailOps::SHRINK
+        } else {
+            FailOps::GROW
+        }
+    }
+}
+
+impl<A: Alloc> Alloc for FailingAlloc<A> {
+    /// Attempts to allocate a block of memory fitting the given [`Layout`], unless a failure is
+    /// injected.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails or a failure is injected.
+    /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.check(FailOps::ALLOC, layout, || self.alloc.alloc(layout))
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.check(FailOps::ALLOC, layout, || self.alloc.alloc_zeroed(layout))
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.alloc.dealloc(ptr, layout);
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.check(FailOps::GROW, new_layout, || {
+            self.alloc.grow(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.check(FailOps::GROW, new_layout, || {
+            self.alloc.grow_zeroed(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.check(FailOps::SHRINK, new_layout, || {
+            self.alloc.shrink(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.check(Self::resize_op(old_layout, new_layout), new_layout, || {
+            self.alloc.realloc(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.check(Self::resize_op(old_layout, new_layout), new_layout, || {
+            self.alloc.realloc_zeroed(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        self.alloc.usable_size(ptr, layout)
+    }
+}
+
+impl<A: Owns> Owns for FailingAlloc<A> {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.alloc.owns(ptr, layout)
+    }
+}
+
+impl<A: Alloc + Default> Default for FailingAlloc<A> {
+    #[inline]
+    fn default() -> FailingAlloc<A> {
+        FailingAlloc::new_in(A::default())
+    }
+}
+
+impl<A: Alloc + Debug> Debug for FailingAlloc<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FailingAlloc")
+            .field("alloc", &self.alloc)
+            .field("ops", &FailOps(self.ops.load(SeqCst)))
+            .field("calls", &self.calls())
+            .field("failures", &self.failures())
+            .finish_non_exhaustive()
+    }
+}
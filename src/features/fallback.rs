@@ -0,0 +1,285 @@
+use crate::{error::AllocError, Alloc, Owns};
+use core::{alloc::Layout, ptr::NonNull};
+
+/// An allocator which tries to allocate using `P`, falling back to `S` if that fails.
+///
+/// Blocks are routed back to the allocator they came from by asking `P` whether it
+/// [owns](Owns::owns) them, so `P` must answer [precisely](Owns::PRECISE); using an imprecise `P`
+/// fails to compile. When `P` fails to grow or shrink a block it owns, the block is moved to `S`,
+/// preserving its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fallback<P: Owns, S: Alloc> {
+    /// The primary allocator.
+    primary: P,
+    /// The secondary allocator.
+    secondary: S,
+}
+
+impl<P: Owns, S: Alloc> Fallback<P, S> {
+    /// Fails to compile if `P` can't tell precisely which blocks it owns.
+    const PRECISE_PRIMARY: () = assert!(
+        P::PRECISE,
+        "the primary allocator of a `Fallback` must answer `owns` precisely"
+    );
+
+    /// Creates a new fallback allocator from a primary and a secondary allocator.
+    #[inline]
+    pub const fn new(primary: P, secondary: S) -> Fallback<P, S> {
+        let () = Self::PRECISE_PRIMARY;
+        Fallback { primary, secondary }
+    }
+
+    /// Gets a reference to the primary allocator.
+    #[inline]
+    pub const fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Gets a reference to the secondary allocator.
+    #[inline]
+    pub const fn secondary(&self) -> &S {
+        &self.secondary
+    }
+
+    /// Splits this allocator into its primary and secondary allocators.
+    #[inline]
+    pub fn into_parts(self) -> (P, S) {
+        (self.primary, self.secondary)
+    }
+
+    /// Returns whether the block at `ptr` came from the primary allocator.
+    #[inline]
+    fn primary_owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        let () = Self::PRECISE_PRIMARY;
+        self.primary.owns(ptr, layout)
+    }
+
+    /// Moves a block owned by the primary allocator to a new block from the secondary one,
+    /// copying as many bytes as fit.
+    #[track_caller]
+    unsafe fn move_to_secondary(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zero: bool,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = if zero {
+            self.secondary.alloc_zeroed(new_layout)?
+        } else {
+            self.secondary.alloc(new_layout)?
+        };
+        let cnt = if old_layout.size() < new_layout.size() {
+            old_layout.size()
+        } else {
+            new_layout.size()
+        };
+        ptr.as_ptr().copy_to_nonoverlapping(new_ptr.as_ptr(), cnt);
+        self.primary.dealloc(ptr, old_layout);
+        Ok(new_ptr)
+    }
+
+    /// Moves the block to the secondary allocator if the primary one failed to resize it.
+    ///
+    /// Like [`alloc`](Fallback::alloc), this falls back on any error except a zero-sized
+    /// layout.
+    #[track_caller]
+    #[inline]
+    unsafe fn or_move(
+        &self,
+        res: Result<NonNull<u8>, AllocError>,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zero: bool,
+    ) -> Result<NonNull<u8>, AllocError> {
+        match res {
+            Err(AllocError::ZeroSizedLayout(p)) => Err(AllocError::ZeroSizedLayout(p)),
+            Err(_) => self.move_to_secondary(ptr, old_layout, new_layout, zero),
+            ok => ok,
+        }
+    }
+}
+
+impl<P: Owns, S: Alloc> Alloc for Fallback<P, S> {
+    /// Attempts to allocate a block of memory fitting the given [`Layout`], first using the
+    /// primary allocator and then the secondary one.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::ZeroSizedLayout`] if the primary allocator rejects `layout` because it has
+    ///   a size of zero.
+    /// - Any error returned by the secondary allocator.
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        match self.primary.alloc(layout) {
+            Err(AllocError::ZeroSizedLayout(p)) => Err(AllocError::ZeroSizedLayout(p)),
+            Err(_) => self.secondary.alloc(layout),
+            ok => ok,
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        match self.primary.alloc_zeroed(layout) {
+            Err(AllocError::ZeroSizedLayout(p)) => Err(AllocError::ZeroSizedLayout(p)),
+            Err(_) => self.secondary.alloc_zeroed(layout),
+            ok => ok,
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        if self.primary_owns(ptr, layout) {
+            self.primary.dealloc(ptr, layout);
+        } else {
+            self.secondary.dealloc(ptr, layout);
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            let res = self.primary.grow(ptr, old_layout, new_layout);
+            self.or_move(res, ptr, old_layout, new_layout, false)
+        } else {
+            self.secondary.grow(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            let res = self.primary.grow_zeroed(ptr, old_layout, new_layout);
+            self.or_move(res, ptr, old_layout, new_layout, true)
+        } else {
+            self.secondary.grow_zeroed(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            let res = self.primary.shrink(ptr, old_layout, new_layout);
+            self.or_move(res, ptr, old_layout, new_layout, false)
+        } else {
+            self.secondary.shrink(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            let res = self.primary.realloc(ptr, old_layout, new_layout);
+            self.or_move(res, ptr, old_layout, new_layout, false)
+        } else {
+            self.secondary.realloc(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            let res = self.primary.realloc_zeroed(ptr, old_layout, new_layout);
+            self.or_move(res, ptr, old_layout, new_layout, true)
+        } else {
+            self.secondary.realloc_zeroed(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        if self.primary_owns(ptr, layout) {
+            self.primary.usable_size(ptr, layout)
+        } else {
+            self.secondary.usable_size(ptr, layout)
+        }
+    }
+}
+
+impl<P: Owns, S: Owns> Owns for Fallback<P, S> {
+    const PRECISE: bool = P::PRECISE && S::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.primary_owns(ptr, layout) || self.secondary.owns(ptr, layout)
+    }
+}
+
+impl<P: Owns + Default, S: Alloc + Default> Default for Fallback<P, S> {
+    #[inline]
+    fn default() -> Fallback<P, S> {
+        Fallback::new(P::default(), S::default())
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<P, S> crate::in_place::ResizeInPlace for Fallback<P, S>
+where
+    P: Owns + crate::in_place::ResizeInPlace,
+    S: crate::in_place::ResizeInPlace,
+{
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            self.primary.grow_in_place(ptr, old_layout, new_size)
+        } else {
+            self.secondary.grow_in_place(ptr, old_layout, new_size)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if self.primary_owns(ptr, old_layout) {
+            self.primary.shrink_in_place(ptr, old_layout, new_size)
+        } else {
+            self.secondary.shrink_in_place(ptr, old_layout, new_size)
+        }
+    }
+}
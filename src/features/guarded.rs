@@ -0,0 +1,410 @@
+use crate::{
+    error::{AllocError, ArithOp, PanicOnViolation, Violation, ViolationHandler, ViolationKind},
+    helpers::{align_up_unchecked, zsl_check},
+    Alloc, AllocPattern, DefaultAlloc, Owns,
+};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug, Formatter},
+    ptr::NonNull,
+};
+
+/// The minimum number of canary bytes on each side of a [`Guarded`] block.
+pub const GUARD_SIZE: usize = 16;
+
+/// The value of every canary byte.
+pub const CANARY: u8 = 0xCA;
+
+/// An allocator which surrounds every block with canary bytes and checks them when the block is
+/// deallocated, grown or shrunk.
+///
+/// Damaged canaries are reported to `H` as a [`Violation`] carrying the block's pointer and
+/// layout. The operation continues after `H` returns.
+///
+/// The canaries before a block are padded to a multiple of its alignment, so the pointers handed
+/// out still honor the requested alignment.
+pub struct Guarded<A: Alloc = DefaultAlloc, H: ViolationHandler = PanicOnViolation> {
+    /// The inner allocator.
+    alloc: A,
+    /// The violation handler.
+    handler: H,
+}
+
+impl Guarded {
+    /// Creates a new guarded allocator backed by the default allocator, which panics on
+    /// violations.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Guarded {
+        Guarded::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> Guarded<A> {
+    /// Creates a new guarded allocator backed by `alloc`, which panics on violations.
+    #[inline]
+    pub const fn new_in(alloc: A) -> Guarded<A> {
+        Guarded::with_handler_in(PanicOnViolation, alloc)
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler> Guarded<A, H> {
+    /// Creates a new guarded allocator backed by `alloc`, which reports violations to `handler`.
+    #[inline]
+    pub const fn with_handler_in(handler: H, alloc: A) -> Guarded<A, H> {
+        Guarded { alloc, handler }
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Gets a reference to the violation handler.
+    #[inline]
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Checks the canaries of the block at `ptr`, reporting any damage, and returns whether they
+    /// were intact.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    pub unsafe fn check(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        if layout.size() == 0 {
+            return true;
+        }
+        let front = front_size(layout);
+        let base = ptr.as_ptr().sub(front);
+        let mut intact = true;
+        if !is_canary(base, front) {
+            intact = false;
+            self.report(ViolationKind::Underflow, ptr, layout);
+        }
+        if !is_canary(ptr.as_ptr().add(layout.size()), GUARD_SIZE) {
+            intact = false;
+            self.report(ViolationKind::Overflow, ptr, layout);
+        }
+        intact
+    }
+
+    #[track_caller]
+    #[inline]
+    fn report(&self, kind: ViolationKind, ptr: NonNull<u8>, layout: Layout) {
+        self.handler.report(&Violation { kind, ptr, layout });
+    }
+
+    /// Allocates a guarded block using `alloc`.
+    #[track_caller]
+    fn alloc_with<F: Fn(&A, Layout) -> Result<NonNull<u8>, AllocError>>(
+        &self,
+        layout: Layout,
+        alloc: F,
+    ) -> Result<NonNull<u8>, AllocError> {
+        zsl_check(layout, |layout| {
+            let outer = outer_layout(layout)?;
+            let base = alloc(&self.alloc, outer)?;
+            unsafe {
+                let ptr = base.as_ptr().add(front_size(layout));
+                write_guards(ptr, layout);
+                Ok(NonNull::new_unchecked(ptr))
+            }
+        })
+    }
+
+    /// Resizes a guarded block in place using the inner allocator, if the alignment stays the
+    /// same. Otherwise, the block is moved.
+    #[track_caller]
+    unsafe fn resize(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zero: bool,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let growing = new_layout.size() >= old_layout.size();
+        if new_layout.align() != old_layout.align()
+            || old_layout.size() == 0
+            || new_layout.size() == 0
+        {
+            return match (growing, zero) {
+                (true, true) => crate::grow(
+                    self,
+                    ptr,
+                    old_layout,
+                    new_layout,
+                    AllocPattern::<fn(usize) -> u8>::Zero,
+                ),
+                (true, false) => crate::grow(
+                    self,
+                    ptr,
+                    old_layout,
+                    new_layout,
+                    AllocPattern::<fn(usize) -> u8>::None,
+                ),
+                (false, _) => crate::shrink(self, ptr, old_layout, new_layout),
+            };
+        }
+
+        self.check(ptr, old_layout);
+        let front = front_size(old_layout);
+        let base = NonNull::new_unchecked(ptr.as_ptr().sub(front));
+        let old_outer = outer_layout(old_layout)?;
+        let new_outer = outer_layout(new_layout)?;
+        let new_base = match (growing, zero) {
+            (true, true) => self.alloc.grow_zeroed(base, old_outer, new_outer)?,
+            (true, false) => self.alloc.grow(base, old_outer, new_outer)?,
+            (false, _) => self.alloc.shrink(base, old_outer, new_outer)?,
+        };
+        let new_ptr = new_base.as_ptr().add(front);
+        if growing && zero {
+            // the old trailing canaries are now part of the block.
+            new_ptr
+                .add(old_layout.size())
+                .write_bytes(0, GUARD_SIZE.min(new_layout.size() - old_layout.size()));
+        }
+        write_guards(new_ptr, new_layout);
+        Ok(NonNull::new_unchecked(new_ptr))
+    }
+}
+
+/// Returns the number of canary bytes before a block of `layout`.
+#[inline]
+const fn front_size(layout: Layout) -> usize {
+    unsafe { align_up_unchecked(GUARD_SIZE, layout.align()) }
+}
+
+/// Returns the layout of the inner block holding a guarded block of `layout`.
+#[inline]
+fn outer_layout(layout: Layout) -> Result<Layout, AllocError> {
+    let front = front_size(layout);
+    let size = match front
+        .checked_add(layout.size())
+        .and_then(|n| n.checked_add(GUARD_SIZE))
+    {
+        Some(size) => size,
+        None => {
+            return Err(AllocError::ArithmeticOverflow(
+                front + GUARD_SIZE,
+                ArithOp::Add,
+                layout.size(),
+            ))
+        }
+    };
+    Layout::from_size_align(size, layout.align())
+        .map_err(|_| AllocError::LayoutError(size, layout.align()))
+}
+
+/// Writes the canaries around the block at `ptr`.
+#[inline]
+unsafe fn write_guards(ptr: *mut u8, layout: Layout) {
+    let front = front_size(layout);
+    ptr.sub(front).write_bytes(CANARY, front);
+    ptr.add(layout.size()).write_bytes(CANARY, GUARD_SIZE);
+}
+
+/// Returns whether `len` bytes at `ptr` are all canaries.
+#[inline]
+unsafe fn is_canary(ptr: *const u8, len: usize) -> bool {
+    (0..len).all(|i| *ptr.add(i) == CANARY)
+}
+
+impl<A: Alloc, H: ViolationHandler> Alloc for Guarded<A, H> {
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.alloc_with(layout, A::alloc)
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.alloc_with(layout, A::alloc_zeroed)
+    }
+
+    /// Checks the canaries of the block at `ptr`, then deallocates it.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+        self.check(ptr, layout);
+        let base = NonNull::new_unchecked(ptr.as_ptr().sub(front_size(layout)));
+        // the layout was valid when the block was allocated.
+        if let Ok(outer) = outer_layout(layout) {
+            self.alloc.dealloc(base, outer);
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() < old_layout.size() {
+            return Err(AllocError::GrowSmallerNewLayout(
+                old_layout.size(),
+                new_layout.size(),
+            ));
+        }
+        self.resize(ptr, old_layout, new_layout, false)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() < old_layout.size() {
+            return Err(AllocError::GrowSmallerNewLayout(
+                old_layout.size(),
+                new_layout.size(),
+            ));
+        }
+        self.resize(ptr, old_layout, new_layout, true)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() > old_layout.size() {
+            return Err(AllocError::ShrinkBiggerNewLayout(
+                old_layout.size(),
+                new_layout.size(),
+            ));
+        }
+        self.resize(ptr, old_layout, new_layout, false)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, false)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.resize(ptr, old_layout, new_layout, true)
+    }
+}
+
+impl<A: Owns, H: ViolationHandler> Owns for Guarded<A, H> {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        if layout.size() == 0 {
+            return false;
+        }
+        let base = ptr.as_ptr().wrapping_sub(front_size(layout));
+        match (NonNull::new(base), outer_layout(layout)) {
+            (Some(base), Ok(outer)) => self.alloc.owns(base, outer),
+            _ => false,
+        }
+    }
+}
+
+impl<A: Alloc + Default, H: ViolationHandler + Default> Default for Guarded<A, H> {
+    #[inline]
+    fn default() -> Guarded<A, H> {
+        Guarded::with_handler_in(H::default(), A::default())
+    }
+}
+
+impl<A: Alloc + Debug, H: ViolationHandler> Debug for Guarded<A, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guarded")
+            .field("alloc", &self.alloc)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, H: ViolationHandler> crate::in_place::ResizeInPlace
+    for Guarded<A, H>
+{
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.resize_in_place(ptr, old_layout, new_size, A::grow_in_place)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.resize_in_place(ptr, old_layout, new_size, A::shrink_in_place)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, H: ViolationHandler> Guarded<A, H> {
+    /// Checks the canaries of the block at `ptr`, resizes its inner block in place using `f` and
+    /// moves the trailing canaries.
+    #[track_caller]
+    unsafe fn resize_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        f: unsafe fn(&A, NonNull<u8>, Layout, usize) -> Result<(), AllocError>,
+    ) -> Result<(), AllocError> {
+        if old_layout.size() == 0 || new_size == 0 {
+            return Err(AllocError::Other(crate::in_place::RESIZE_IP_ZS));
+        }
+        let new_layout = Layout::from_size_align(new_size, old_layout.align())
+            .map_err(|_| AllocError::LayoutError(new_size, old_layout.align()))?;
+        self.check(ptr, old_layout);
+        let base = NonNull::new_unchecked(ptr.as_ptr().sub(front_size(old_layout)));
+        f(
+            &self.alloc,
+            base,
+            outer_layout(old_layout)?,
+            outer_layout(new_layout)?.size(),
+        )?;
+        write_guards(ptr.as_ptr(), new_layout);
+        Ok(())
+    }
+}
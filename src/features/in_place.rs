@@ -16,7 +16,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     unsafe fn grow_in_place(
         &self,
         ptr: NonNull<u8>,
@@ -37,7 +37,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_in_place_zeroed(
         &self,
@@ -62,7 +62,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_in_place_patterned<F: Fn(usize) -> u8 + Clone>(
         &self,
@@ -99,7 +99,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_in_place_filled(
         &self,
@@ -129,7 +129,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     unsafe fn shrink_in_place(
         &self,
         ptr: NonNull<u8>,
@@ -153,7 +153,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_in_place(
         &self,
@@ -184,7 +184,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_in_place_zeroed(
         &self,
@@ -216,7 +216,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_in_place_patterned<F: Fn(usize) -> u8 + Clone>(
         &self,
@@ -249,7 +249,7 @@ pub trait ResizeInPlace: Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_in_place_filled(
         &self,
@@ -266,10 +266,20 @@ pub trait ResizeInPlace: Alloc {
     }
 }
 
-#[cfg(any(feature = "jemalloc", feature = "mimalloc"))]
-const RESIZE_IP_ZS: &str = "zero-sized resize in place was requested";
-#[cfg(any(feature = "jemalloc", feature = "mimalloc"))]
-const CANNOT_RESIZE_IP: &str = "cannot resize in place";
+#[cfg(any(
+    feature = "jemalloc",
+    feature = "mimalloc",
+    feature = "bump",
+    feature = "guarded"
+))]
+pub(crate) const RESIZE_IP_ZS: &str = "zero-sized resize in place was requested";
+#[cfg(any(
+    feature = "jemalloc",
+    feature = "mimalloc",
+    feature = "bump",
+    feature = "segregate"
+))]
+pub(crate) const CANNOT_RESIZE_IP: &str = "cannot resize in place";
 
 #[cfg(feature = "jemalloc")]
 impl ResizeInPlace for crate::external_alloc::jemalloc::Jemalloc {
@@ -0,0 +1,357 @@
+use crate::{error::AllocError, spin::SpinLock, Alloc, DefaultAlloc, Owns};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
+    alloc::Layout,
+    cmp::Reverse,
+    fmt::{self, Debug, Display, Formatter},
+    panic::Location,
+    ptr::NonNull,
+};
+
+/// The live allocations from a single call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Leak {
+    /// The call site the blocks were allocated at.
+    pub location: &'static Location<'static>,
+    /// The number of live blocks.
+    pub count: usize,
+    /// The total size of the live blocks, in bytes.
+    pub bytes: usize,
+}
+
+/// The live allocations of a [`LeakCheck`], grouped by call site.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LeakReport {
+    /// The call sites with live allocations, with the most bytes first.
+    pub leaks: Vec<Leak>,
+}
+
+impl LeakReport {
+    /// Returns whether there are no live allocations.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leaks.is_empty()
+    }
+
+    /// Returns the total number of live blocks.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.leaks.iter().map(|leak| leak.count).sum()
+    }
+
+    /// Returns the total size of the live blocks, in bytes.
+    #[must_use]
+    pub fn bytes(&self) -> usize {
+        self.leaks.iter().map(|leak| leak.bytes).sum()
+    }
+}
+
+impl Display for LeakReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} live allocations ({} bytes)",
+            self.count(),
+            self.bytes()
+        )?;
+        for leak in &self.leaks {
+            write!(
+                f,
+                "\n  {} bytes in {} blocks allocated at {}",
+                leak.bytes, leak.count, leak.location
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An allocator which records the call site of every live allocation, to find leaks.
+///
+/// Allocation methods are `#[track_caller]`, so the recorded call site is the first caller which
+/// isn't itself `#[track_caller]`. Resizing a block keeps the call site it was allocated at.
+///
+/// Use [`report`](LeakCheck::report) or [`assert_no_leaks`](LeakCheck::assert_no_leaks) at the
+/// end of a test, or [`report_on_drop`](LeakCheck::report_on_drop) to check automatically.
+pub struct LeakCheck<A: Alloc = DefaultAlloc> {
+    /// The inner allocator.
+    alloc: A,
+    /// The size and call site of every live block, keyed by address.
+    live: SpinLock<BTreeMap<usize, (usize, &'static Location<'static>)>>,
+    /// Whether to panic on drop if there are live blocks.
+    report_on_drop: bool,
+}
+
+impl LeakCheck {
+    /// Creates a new leak checker backed by the default allocator.
+    #[must_use]
+    #[inline]
+    pub fn new() -> LeakCheck {
+        LeakCheck::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> LeakCheck<A> {
+    /// Creates a new leak checker backed by `alloc`.
+    #[inline]
+    pub fn new_in(alloc: A) -> LeakCheck<A> {
+        LeakCheck {
+            alloc,
+            live: SpinLock::new(BTreeMap::new()),
+            report_on_drop: false,
+        }
+    }
+
+    /// Makes this leak checker panic with its report when dropped, if there are live blocks.
+    ///
+    /// With `std`, this doesn't panic while the thread is already panicking.
+    #[must_use]
+    #[inline]
+    pub fn report_on_drop(mut self) -> LeakCheck<A> {
+        self.report_on_drop = true;
+        self
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the number of live blocks.
+    pub fn live(&self) -> usize {
+        self.live.lock().len()
+    }
+
+    /// Returns the live blocks, grouped by the call site they were allocated at.
+    pub fn report(&self) -> LeakReport {
+        let mut sites: BTreeMap<&'static Location<'static>, (usize, usize)> = BTreeMap::new();
+        for (size, location) in self.live.lock().values() {
+            let site = sites.entry(*location).or_insert((0, 0));
+            site.0 += 1;
+            site.1 += size;
+        }
+        let mut leaks: Vec<Leak> = sites
+            .into_iter()
+            .map(|(location, (count, bytes))| Leak {
+                location,
+                count,
+                bytes,
+            })
+            .collect();
+        leaks.sort_by_key(|leak| Reverse(leak.bytes));
+        LeakReport { leaks }
+    }
+
+    /// Asserts that there are no live blocks.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic with the [report](LeakCheck::report) if there are live blocks.
+    #[track_caller]
+    pub fn assert_no_leaks(&self) {
+        let report = self.report();
+        assert!(report.is_empty(), "{report}");
+    }
+
+    /// Records a new live block.
+    #[track_caller]
+    #[inline]
+    fn record(
+        &self,
+        res: Result<NonNull<u8>, AllocError>,
+        size: usize,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let ptr = res?;
+        self.live
+            .lock()
+            .insert(ptr.as_ptr() as usize, (size, Location::caller()));
+        Ok(ptr)
+    }
+
+    /// Moves the record of a resized block.
+    #[track_caller]
+    #[inline]
+    fn moved(
+        &self,
+        ptr: NonNull<u8>,
+        res: Result<NonNull<u8>, AllocError>,
+        new_size: usize,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = res?;
+        let mut live = self.live.lock();
+        let location = match live.remove(&(ptr.as_ptr() as usize)) {
+            Some((_, location)) => location,
+            None => Location::caller(),
+        };
+        live.insert(new_ptr.as_ptr() as usize, (new_size, location));
+        Ok(new_ptr)
+    }
+}
+
+impl<A: Alloc> Alloc for LeakCheck<A> {
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.record(self.alloc.alloc(layout), layout.size())
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.record(self.alloc.alloc_zeroed(layout), layout.size())
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.lock().remove(&(ptr.as_ptr() as usize));
+        self.alloc.dealloc(ptr, layout);
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.moved(
+            ptr,
+            self.alloc.grow(ptr, old_layout, new_layout),
+            new_layout.size(),
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.moved(
+            ptr,
+            self.alloc.grow_zeroed(ptr, old_layout, new_layout),
+            new_layout.size(),
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.moved(
+            ptr,
+            self.alloc.shrink(ptr, old_layout, new_layout),
+            new_layout.size(),
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.moved(
+            ptr,
+            self.alloc.realloc(ptr, old_layout, new_layout),
+            new_layout.size(),
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.moved(
+            ptr,
+            self.alloc.realloc_zeroed(ptr, old_layout, new_layout),
+            new_layout.size(),
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        self.alloc.usable_size(ptr, layout)
+    }
+}
+
+impl<A: Owns> Owns for LeakCheck<A> {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.alloc.owns(ptr, layout)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace> crate::in_place::ResizeInPlace for LeakCheck<A> {
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.alloc.grow_in_place(ptr, old_layout, new_size)?;
+        self.moved(ptr, Ok(ptr), new_size).map(|_| ())
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.alloc.shrink_in_place(ptr, old_layout, new_size)?;
+        self.moved(ptr, Ok(ptr), new_size).map(|_| ())
+    }
+}
+
+impl<A: Alloc> Drop for LeakCheck<A> {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if std::thread::panicking() {
+            return;
+        }
+        if self.report_on_drop {
+            self.assert_no_leaks();
+        }
+    }
+}
+
+impl<A: Alloc + Default> Default for LeakCheck<A> {
+    #[inline]
+    fn default() -> LeakCheck<A> {
+        LeakCheck::new_in(A::default())
+    }
+}
+
+impl<A: Alloc + Debug> Debug for LeakCheck<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeakCheck")
+            .field("alloc", &self.alloc)
+            .field("live", &self.live())
+            .field("report_on_drop", &self.report_on_drop)
+            .finish()
+    }
+}
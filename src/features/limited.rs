@@ -0,0 +1,297 @@
+use crate::{error::AllocError, Alloc, DefaultAlloc, Owns};
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering::{AcqRel, Acquire, Relaxed, Release},
+    },
+};
+
+/// A memory budget, tracking the number of live bytes against a limit.
+///
+/// A budget can be shared between several [`Limited`] allocators by giving each of them a
+/// reference to it, or an `Arc` of it.
+#[derive(Debug, Default)]
+pub struct Budget {
+    /// The maximum number of live bytes.
+    limit: AtomicUsize,
+    /// The number of live bytes.
+    used: AtomicUsize,
+}
+
+impl Budget {
+    /// Creates a new budget allowing up to `limit` live bytes.
+    #[must_use]
+    #[inline]
+    pub const fn new(limit: usize) -> Budget {
+        Budget {
+            limit: AtomicUsize::new(limit),
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the maximum number of live bytes.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit.load(Acquire)
+    }
+
+    /// Sets the maximum number of live bytes.
+    ///
+    /// Lowering the limit below the number of bytes in use doesn't free anything, but makes
+    /// further allocations fail until enough is deallocated.
+    #[inline]
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Release);
+    }
+
+    /// Returns the number of live bytes.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.used.load(Acquire)
+    }
+
+    /// Returns the number of bytes which can still be allocated.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.limit().saturating_sub(self.used())
+    }
+
+    /// Reserves `bytes` bytes of the budget.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::LimitExceeded`] if the reservation would exceed the limit.
+    pub fn reserve(&self, bytes: usize) -> Result<(), AllocError> {
+        let mut used = self.used.load(Relaxed);
+        loop {
+            let limit = self.limit.load(Acquire);
+            let new = match used.checked_add(bytes) {
+                Some(new) if new <= limit => new,
+                _ => return Err(AllocError::LimitExceeded(limit, used, bytes)),
+            };
+            match self.used.compare_exchange_weak(used, new, AcqRel, Relaxed) {
+                Ok(_) => return Ok(()),
+                Err(cur) => used = cur,
+            }
+        }
+    }
+
+    /// Returns `bytes` previously [reserved](Budget::reserve) bytes to the budget.
+    #[inline]
+    pub fn release(&self, bytes: usize) {
+        self.used.fetch_sub(bytes, Release);
+    }
+}
+
+/// An allocator which fails once the number of live bytes allocated through it would exceed a
+/// [`Budget`].
+///
+/// Bytes are counted using the sizes of the layouts passed to the allocator. Allocations which
+/// would exceed the budget fail with [`AllocError::LimitExceeded`].
+#[derive(Debug, Default)]
+pub struct Limited<A: Alloc = DefaultAlloc, B: Borrow<Budget> = Budget> {
+    /// The inner allocator.
+    alloc: A,
+    /// The budget.
+    budget: B,
+}
+
+impl Limited {
+    /// Creates a new allocator backed by the default allocator, with its own budget of `limit`
+    /// bytes.
+    #[must_use]
+    #[inline]
+    pub const fn new(limit: usize) -> Limited {
+        Limited::with_budget_in(Budget::new(limit), DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> Limited<A> {
+    /// Creates a new allocator backed by `alloc`, with its own budget of `limit` bytes.
+    #[inline]
+    pub const fn new_in(limit: usize, alloc: A) -> Limited<A> {
+        Limited::with_budget_in(Budget::new(limit), alloc)
+    }
+}
+
+impl<A: Alloc, B: Borrow<Budget>> Limited<A, B> {
+    /// Creates a new allocator backed by `alloc`, using `budget`.
+    ///
+    /// Pass `&Budget` or `Arc<Budget>` to share one budget between several allocators.
+    #[inline]
+    pub const fn with_budget_in(budget: B, alloc: A) -> Limited<A, B> {
+        Limited { alloc, budget }
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Gets a reference to the budget.
+    #[inline]
+    pub fn budget(&self) -> &Budget {
+        self.budget.borrow()
+    }
+
+    /// Reserves `bytes` bytes, runs `f`, and releases the bytes again if `f` fails.
+    #[track_caller]
+    #[inline]
+    fn reserved<T, F: FnOnce() -> Result<T, AllocError>>(
+        &self,
+        bytes: usize,
+        f: F,
+    ) -> Result<T, AllocError> {
+        self.budget().reserve(bytes)?;
+        let res = f();
+        if res.is_err() {
+            self.budget().release(bytes);
+        }
+        res
+    }
+}
+
+impl<A: Alloc, B: Borrow<Budget>> Alloc for Limited<A, B> {
+    /// Attempts to allocate a block of memory fitting the given [`Layout`], if the budget allows.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::LimitExceeded`] if the allocation would exceed the budget.
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.reserved(layout.size(), || self.alloc.alloc(layout))
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.reserved(layout.size(), || self.alloc.alloc_zeroed(layout))
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.alloc.dealloc(ptr, layout);
+        self.budget().release(layout.size());
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.reserved(new_layout.size().saturating_sub(old_layout.size()), || {
+            self.alloc.grow(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        self.reserved(new_layout.size().saturating_sub(old_layout.size()), || {
+            self.alloc.grow_zeroed(ptr, old_layout, new_layout)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.alloc.shrink(ptr, old_layout, new_layout)?;
+        self.budget()
+            .release(old_layout.size().saturating_sub(new_layout.size()));
+        Ok(new_ptr)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.reserved(new_layout.size().saturating_sub(old_layout.size()), || {
+            self.alloc.realloc(ptr, old_layout, new_layout)
+        })?;
+        self.budget()
+            .release(old_layout.size().saturating_sub(new_layout.size()));
+        Ok(new_ptr)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.reserved(new_layout.size().saturating_sub(old_layout.size()), || {
+            self.alloc.realloc_zeroed(ptr, old_layout, new_layout)
+        })?;
+        self.budget()
+            .release(old_layout.size().saturating_sub(new_layout.size()));
+        Ok(new_ptr)
+    }
+}
+
+impl<A: Owns, B: Borrow<Budget>> Owns for Limited<A, B> {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.alloc.owns(ptr, layout)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, B: Borrow<Budget>> crate::in_place::ResizeInPlace
+    for Limited<A, B>
+{
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.reserved(new_size.saturating_sub(old_layout.size()), || {
+            self.alloc.grow_in_place(ptr, old_layout, new_size)
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.alloc.shrink_in_place(ptr, old_layout, new_size)?;
+        self.budget()
+            .release(old_layout.size().saturating_sub(new_size));
+        Ok(())
+    }
+}
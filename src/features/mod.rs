@@ -4,13 +4,46 @@ pub mod alloc_ext;
 #[cfg(feature = "alloc_slice")]
 /// Slice-specific allocator abstractions.
 pub mod alloc_slice;
+#[cfg(feature = "bump")]
+/// Bump/arena allocation.
+pub mod bump;
+#[cfg(feature = "checked")]
+/// Layout checking for allocations.
+pub mod checked;
+#[cfg(feature = "failing")]
+/// Fault injection for testing allocation failures.
+pub mod failing;
+#[cfg(feature = "fallback")]
+/// Allocator composition with a fallback.
+pub mod fallback;
+#[cfg(feature = "guarded")]
+/// Red zone checking for allocations.
+pub mod guarded;
 #[cfg(feature = "resize_in_place")]
 /// Reallocation in-place.
 pub mod in_place;
+#[cfg(feature = "leak_check")]
+/// Leak detection for allocations.
+pub mod leak_check;
+#[cfg(feature = "limited")]
+/// Memory limits for allocators.
+pub mod limited;
 
 #[cfg(feature = "owned")]
 /// Owned data types.
 pub mod owned;
+#[cfg(feature = "poison")]
+/// Memory poisoning for allocations.
+pub mod poison;
+#[cfg(feature = "pool")]
+/// Fixed-size object pooling.
+pub mod pool;
+#[cfg(feature = "segregate")]
+/// Allocator composition by size.
+pub mod segregate;
 #[cfg(feature = "stats")]
 /// Allocation statistic gathering and reporting.
 pub mod stats;
+#[cfg(feature = "zeroizing")]
+/// Secure erasure of freed memory.
+pub mod zeroizing;
@@ -3,9 +3,13 @@ use crate::{
     error::ArithOp,
     helpers::{
         alloc_slice, dealloc_n, layout_or_sz_align, nonnull_slice_from_raw_parts,
-        slice_ptr_from_raw_parts, SliceAllocGuard, TRUNC_LGR,
+        nonnull_slice_len, secure_zero, slice_ptr_from_raw_parts, SliceAllocGuard, TRUNC_LGR,
+    },
+    owned::{
+        drain::range_in,
+        Drain, HeapVal, OwnedIter, Splice,
+        VariableError::{Hard, Soft},
     },
-    owned::VariableError::{Hard, Soft},
     type_props::SizedProps,
     Alloc,
     AllocError,
@@ -20,13 +24,11 @@ use core::{
     marker::PhantomData,
     mem::ManuallyDrop,
     mem::{transmute, MaybeUninit},
-    ops::{Deref, DerefMut, Index, IndexMut},
+    ops::{Deref, DerefMut, Index, IndexMut, RangeBounds},
     ptr::{self, replace, NonNull},
     slice::{self, SliceIndex},
     mem::align_of
 };
-// TODO: more array utilities like into_array, into_flattened() to reverse into_chunks, etc.
-// TODO: use actual slices for things like insert_slice instead of owned buffers
 
 /// Calculates the actual size for a buffer, taking into account `T`'s potentially ZST status.
 #[inline]
@@ -69,6 +71,52 @@ impl<S: Display, H: Display> Display for VariableError<S, H> {
 #[cfg(feature = "std")]
 impl<S: Display + Debug, H: Display + Debug> std::error::Error for VariableError<S, H> {}
 
+/// The strategy an [`OwnedBuf`] uses to pick a new size when it has to grow automatically.
+///
+/// This is used by [`init_next_grow`](OwnedBuf::init_next_grow),
+/// [`try_insert_grow`](OwnedBuf::try_insert_grow) and
+/// [`try_insert_slice_grow`](OwnedBuf::try_insert_slice_grow). The computed size is always at
+/// least the required size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrowthPolicy {
+    /// Grow to exactly the required size.
+    Exact,
+    /// Double the current size. This is the default.
+    Double,
+    /// Grow the current size by half.
+    OneAndHalf,
+    /// Grow the current size by a fixed number of elements. A chunk of `0` behaves like
+    /// [`Exact`](GrowthPolicy::Exact).
+    Chunk(usize),
+}
+
+impl GrowthPolicy {
+    /// Computes the size to grow a buffer of `size` elements to, given at least `required`
+    /// elements are needed.
+    #[must_use]
+    #[inline]
+    pub const fn next_size(self, size: usize, required: usize) -> usize {
+        let grown = match self {
+            GrowthPolicy::Exact => required,
+            GrowthPolicy::Double => size.saturating_mul(2),
+            GrowthPolicy::OneAndHalf => size.saturating_add(size / 2),
+            GrowthPolicy::Chunk(chunk) => size.saturating_add(chunk),
+        };
+        if grown < required {
+            required
+        } else {
+            grown
+        }
+    }
+}
+
+impl Default for GrowthPolicy {
+    #[inline]
+    fn default() -> GrowthPolicy {
+        GrowthPolicy::Double
+    }
+}
+
 //noinspection RsUnnecessaryQualifications
 /// An owned buffer of multiple `T` allocated using `A`.
 ///
@@ -87,6 +135,8 @@ pub struct OwnedBuf<T, A: Alloc = DefaultAlloc> {
     size: usize,
     /// The allocator.
     alloc: A,
+    /// The policy used when the buffer grows automatically.
+    growth: GrowthPolicy,
     _marker: PhantomData<T>,
 }
 
@@ -97,7 +147,7 @@ impl<T> OwnedBuf<T> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn new(len: usize) -> Result<OwnedBuf<T>, AllocError> {
         OwnedBuf::new_in(len, DefaultAlloc)
@@ -119,18 +169,19 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn new_in(len: usize, alloc: A) -> Result<OwnedBuf<T, A>, AllocError> {
         Ok(OwnedBuf {
             buf: if T::IS_ZST {
                 NonNull::dangling()
             } else {
-                alloc_slice::<T, A>(&alloc, len, A::alloc)?.cast()
+                alloc_slice::<T, A>(&alloc, len, false)?.cast()
             },
             init: 0,
             size: len,
             alloc,
+            growth: GrowthPolicy::Double,
             _marker: PhantomData,
         })
     }
@@ -145,6 +196,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
             init: 0,
             size: 0,
             alloc,
+            growth: GrowthPolicy::Double,
             _marker: PhantomData,
         }
     }
@@ -176,6 +228,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
             init,
             size: actual_size::<T>(size),
             alloc,
+            growth: GrowthPolicy::Double,
             _marker: PhantomData,
         }
     }
@@ -218,6 +271,26 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         &mut self.alloc
     }
 
+    /// Returns the policy used when the buffer grows automatically.
+    #[inline]
+    pub const fn growth_policy(&self) -> GrowthPolicy {
+        self.growth
+    }
+
+    /// Sets the policy used when the buffer grows automatically.
+    #[inline]
+    pub const fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.growth = policy;
+    }
+
+    /// Returns the buffer with its growth policy set to `policy`.
+    #[must_use]
+    #[inline]
+    pub const fn with_growth_policy(mut self, policy: GrowthPolicy) -> OwnedBuf<T, A> {
+        self.growth = policy;
+        self
+    }
+
     /// Gets a pointer to the entire buffer.
     #[inline]
     pub const fn buf_ptr(&self) -> NonNull<[MaybeUninit<T>]> {
@@ -294,7 +367,8 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         Ok(())
     }
 
-    /// Initializes the next element, growing the buffer if necessary.
+    /// Initializes the next element, growing the buffer according to its [`GrowthPolicy`] if
+    /// necessary.
     ///
     /// # Errors
     ///
@@ -302,9 +376,10 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    #[track_caller]
     #[inline]
     pub fn init_next_grow(&mut self, val: T) -> Result<(), AllocError> {
-        self.expand_to_fit(self.init + 1)?;
+        self.grow_amortized(self.init + 1)?;
         unsafe {
             self.init_next_unchecked(val);
         }
@@ -316,7 +391,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// # Safety
     ///
     /// The caller must ensure `self.init < self.size`
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn init_next_unchecked(&mut self, val: T) {
         self.buf.as_ptr().add(self.init).write(val);
@@ -339,7 +414,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// # Safety
     ///
     /// The caller must ensure there is an initialized element to remove.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn remove_last_unchecked(&mut self) -> T {
         self.init -= 1;
@@ -361,7 +436,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// # Safety
     ///
     /// The caller must ensure the index is in the bounds of the initialized buffer.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn remove_unchecked(&mut self, idx: usize) -> T {
         let src = self.get_ptr_unchecked(idx);
@@ -401,7 +476,8 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         }
     }
 
-    /// Attempts to insert `val` at the given `idx`, growing if necessary.
+    /// Attempts to insert `val` at the given `idx`, growing according to the buffer's
+    /// [`GrowthPolicy`] if necessary.
     ///
     /// # Errors
     ///
@@ -412,6 +488,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// `alloc_err` may be:
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    #[track_caller]
     pub fn try_insert_grow(
         &mut self,
         idx: usize,
@@ -420,7 +497,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         if idx > self.init {
             return Err(Soft(val));
         }
-        if let Err(e) = self.expand_to_fit(self.init + 1) {
+        if let Err(e) = self.grow_amortized(self.init + 1) {
             return Err(Hard((val, e)));
         }
 
@@ -456,18 +533,101 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     pub const unsafe fn insert_unchecked(&mut self, idx: usize, val: T) {
         let dst = self.get_ptr_unchecked(idx);
         if idx != self.init {
-            dst.as_ptr()
-                .copy_to_nonoverlapping(dst.as_ptr().add(1), self.init - idx);
+            dst.as_ptr().copy_to(dst.as_ptr().add(1), self.init - idx);
         }
         dst.as_ptr().write(val);
         self.init += 1;
     }
 
-    // TODO: add extending
+    /// Clones and appends all elements of `slice`, growing according to the buffer's
+    /// [`GrowthPolicy`] if necessary.
+    ///
+    /// Use [`extend_from_copy_slice`](OwnedBuf::extend_from_copy_slice) to copy `T: Copy`
+    /// elements in bulk instead.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    #[track_caller]
+    #[inline]
+    pub fn extend_from_slice(&mut self, slice: &[T]) -> Result<(), AllocError>
+    where
+        T: Clone,
+    {
+        slice.extend_ob(self)
+    }
+
+    /// Copies all elements of `slice` to the end of the buffer in one go, growing according to the
+    /// buffer's [`GrowthPolicy`] if necessary.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    #[track_caller]
+    pub fn extend_from_copy_slice(&mut self, slice: &[T]) -> Result<(), AllocError>
+    where
+        T: Copy,
+    {
+        self.grow_amortized(self.required(slice.len())?)?;
+        unsafe {
+            slice
+                .as_ptr()
+                .copy_to_nonoverlapping(self.buf.as_ptr().add(self.init), slice.len());
+        }
+        self.init += slice.len();
+        Ok(())
+    }
+
+    /// Appends all elements of `iter`, growing according to the buffer's [`GrowthPolicy`] if
+    /// necessary.
+    ///
+    /// Space for the lower bound of the iterator's size hint is reserved up front.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    ///
+    /// On error, the elements appended so far are kept and the element which didn't fit is
+    /// dropped along with the rest of the iterator.
+    #[track_caller]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), AllocError> {
+        let mut iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.grow_amortized(self.required(lower)?)?;
+
+        for val in iter.by_ref() {
+            if self.init == self.size() {
+                self.grow_amortized(self.required(1)?)?;
+            }
+            unsafe {
+                self.init_next_unchecked(val);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the size needed to fit `additional` more initialized elements.
+    #[inline]
+    fn required(&self, additional: usize) -> Result<usize, AllocError> {
+        self.init
+            .checked_add(additional)
+            .ok_or(AllocError::ArithmeticOverflow(
+                self.init,
+                ArithOp::Add,
+                additional,
+            ))
+    }
 
     /// Placeholder docs
     #[allow(clippy::missing_errors_doc)]
     #[allow(clippy::type_complexity)]
+    #[track_caller]
     pub fn replace_last_slice<A2: Alloc>(
         &mut self,
         slice: OwnedBuf<T, A2>,
@@ -491,6 +651,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// Placeholder docs
     #[allow(clippy::missing_errors_doc)]
     #[allow(clippy::type_complexity)]
+    #[track_caller]
     pub fn replace_slice<A2: Alloc>(
         &mut self,
         idx: usize,
@@ -508,7 +669,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         let new_init = idx + cnt - overlap_cnt;
 
         // grow if needed
-        if let Err(e) = self.expand_to_fit(new_init) {
+        if let Err(e) = self.grow_amortized(new_init) {
             return Err(Hard((slice, e)));
         }
 
@@ -519,7 +680,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         };
 
         // allocate space for the removed elements, reusing the input buffer's allocator
-        let out_buf = match alloc_slice::<T, A2>(&slice.alloc, overlap_cnt, A2::alloc) {
+        let out_buf = match alloc_slice::<T, A2>(&slice.alloc, overlap_cnt, false) {
             Ok(mem) => mem.cast::<T>(),
             Err(e) => return Err(Hard((slice, e))),
         };
@@ -552,7 +713,8 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     }
 
     #[allow(clippy::type_complexity)]
-    /// Attempts to insert `slice` at `idx`, growing if necessary.
+    /// Attempts to insert `slice` at `idx`, growing according to the buffer's [`GrowthPolicy`] if
+    /// necessary.
     ///
     /// # Errors
     ///
@@ -563,6 +725,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// `alloc_err` may be:
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    #[track_caller]
     pub fn try_insert_slice_grow<A2: Alloc>(
         &mut self,
         idx: usize,
@@ -571,7 +734,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         if idx > self.init {
             return Err(Soft(slice));
         }
-        if let Err(e) = self.expand_to_fit(self.init + slice.len()) {
+        if let Err(e) = self.grow_amortized(self.init + slice.len()) {
             return Err(Hard((slice, e)));
         }
 
@@ -618,7 +781,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         // shift elements over to make space as necessary
         if idx != self.init {
             dst.as_ptr()
-                .copy_to_nonoverlapping(dst.as_ptr().add(slice.init), self.init - idx);
+                .copy_to(dst.as_ptr().add(slice.init), self.init - idx);
         }
         // pointer to initialized elements
         let src = slice.as_slice_ptr().as_ptr();
@@ -637,6 +800,158 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         self.init += len;
     }
 
+    /// Attempts to insert clones of the elements of `slice` at the given `idx`.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(slice)` if the index is out of bounds, or there is no space for some elements of the
+    ///   slice.
+    pub fn try_insert_from_slice<'s>(&mut self, idx: usize, slice: &'s [T]) -> Result<(), &'s [T]>
+    where
+        T: Clone,
+    {
+        if idx > self.init || self.size() - self.init < slice.len() {
+            return Err(slice);
+        }
+
+        unsafe {
+            self.insert_from_slice_unchecked(idx, slice);
+        }
+
+        Ok(())
+    }
+
+    /// Attempts to insert clones of the elements of `slice` at the given `idx`, growing according
+    /// to the buffer's [`GrowthPolicy`] if necessary.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(Soft(slice))` if the index is out of bounds.
+    /// - `Err(Hard((slice, alloc_err)))` if the index is in bounds, but the buffer needs more
+    ///   space and allocation for an expansion fails.
+    ///
+    /// `alloc_err` may be:
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    #[allow(clippy::type_complexity)]
+    #[track_caller]
+    pub fn try_insert_from_slice_grow<'s>(
+        &mut self,
+        idx: usize,
+        slice: &'s [T],
+    ) -> Result<(), VariableError<&'s [T], (&'s [T], AllocError)>>
+    where
+        T: Clone,
+    {
+        if idx > self.init {
+            return Err(Soft(slice));
+        }
+        if let Err(e) = self
+            .required(slice.len())
+            .and_then(|required| self.grow_amortized(required))
+        {
+            return Err(Hard((slice, e)));
+        }
+
+        unsafe {
+            self.insert_from_slice_unchecked(idx, slice);
+        }
+
+        Ok(())
+    }
+
+    /// Inserts clones of the elements of `slice` at the given `idx`.
+    ///
+    /// If a clone panics, the elements cloned so far are kept and the buffer is left in a valid
+    /// state.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure:
+    /// - the index is in bounds
+    /// - the initialized length plus the length of the slice will not go outside of the allocated
+    ///   buffer.
+    #[inline]
+    pub unsafe fn insert_from_slice_unchecked(&mut self, idx: usize, slice: &[T])
+    where
+        T: Clone,
+    {
+        slice.insert_ob(self, idx);
+    }
+
+    /// Replaces the initialized elements starting at `idx` with clones of the elements of
+    /// `slice`, growing according to the buffer's [`GrowthPolicy`] if `slice` reaches past the
+    /// initialized elements. The replaced elements are dropped.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(Soft(slice))` if the index is out of bounds.
+    /// - `Err(Hard((slice, alloc_err)))` if the index is in bounds, but the buffer needs more
+    ///   space and allocation for an expansion fails. Nothing is replaced in this case.
+    ///
+    /// `alloc_err` may be:
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    #[allow(clippy::type_complexity)]
+    #[track_caller]
+    pub fn replace_from_slice<'s>(
+        &mut self,
+        idx: usize,
+        slice: &'s [T],
+    ) -> Result<(), VariableError<&'s [T], (&'s [T], AllocError)>>
+    where
+        T: Clone,
+    {
+        if idx > self.init {
+            return Err(Soft(slice));
+        }
+        let (overlap, tail) = slice.split_at(slice.len().min(self.init - idx));
+        if let Err(e) = self
+            .required(tail.len())
+            .and_then(|required| self.grow_amortized(required))
+        {
+            return Err(Hard((slice, e)));
+        }
+
+        self.as_slice_mut()[idx..idx + overlap.len()].clone_from_slice(overlap);
+        match tail.extend_ob(self) {
+            Ok(()) => Ok(()),
+            // we made space for the tail above.
+            Err(_) => unsafe { core::hint::unreachable_unchecked() },
+        }
+    }
+
+    /// Inserts the elements of `iter` at the given `idx`, growing according to the buffer's
+    /// [`GrowthPolicy`] if necessary.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(Soft(iter))` if the index is out of bounds. The iterator is returned unconsumed.
+    /// - `Err(Hard(alloc_err))` if the buffer needs more space and allocation for an expansion
+    ///   fails. The elements inserted so far are kept, and the rest of the iterator is dropped.
+    ///
+    /// `alloc_err` may be:
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    #[track_caller]
+    pub fn try_insert_iter_grow<I: IntoIterator<Item = T>>(
+        &mut self,
+        idx: usize,
+        iter: I,
+    ) -> Result<(), VariableError<I, AllocError>> {
+        if idx > self.init {
+            return Err(Soft(iter));
+        }
+        match self.splice(idx..idx, iter) {
+            Some(splice) => splice.finish().map_err(Hard),
+            // the index was checked above.
+            None => unsafe { core::hint::unreachable_unchecked() },
+        }
+    }
+
     /// Removes exactly `len` elements from this buffer, starting at `idx`, and returns them in a
     /// new [`OwnedBuf`] with the same allocator (cloned).
     ///
@@ -847,7 +1162,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// # Safety
     ///
     /// The caller must ensure the index is in bounds.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn get_ptr_unchecked(&self, idx: usize) -> NonNull<T> {
         unsafe { NonNull::new_unchecked(self.buf.as_ptr().add(idx)) }
@@ -858,7 +1173,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// # Safety
     ///
     /// The caller must ensure the index is in bounds.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn get_unchecked(&self, idx: usize) -> &T {
         unsafe { &*self.get_ptr_unchecked(idx).as_ptr() }
@@ -869,7 +1184,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// # Safety
     ///
     /// The caller must ensure the index is in bounds.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn get_mut_unchecked(&mut self, idx: usize) -> &mut T {
         unsafe { &mut *self.get_ptr_unchecked(idx).as_ptr() }
@@ -1025,7 +1340,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// The caller must ensure the parameters are in bounds, meaning:
     /// - If using the slice as initialized data, `start + len < self.init`.
     /// - If using the slice as uninitialized data, `start + len < self.size`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn get_slice_ptr_unchecked(&self, start: usize, len: usize) -> NonNull<[T]> {
         nonnull_slice_from_raw_parts(self.get_ptr_unchecked(start), len)
@@ -1038,7 +1353,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// The caller must ensure the parameters are in bounds, meaning:
     /// - If using the slice as initialized data, `start + len < self.init`.
     /// - If using the slice as uninitialized data, `start + len < self.size`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn get_slice_unchecked(&self, start: usize, len: usize) -> &[T] {
         unsafe { &*self.get_slice_ptr_unchecked(start, len).as_ptr() }
@@ -1051,7 +1366,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// The caller must ensure the parameters are in bounds, meaning:
     /// - If using the slice as initialized data, `start + len < self.init`.
     /// - If using the slice as uninitialized data, `start + len < self.size`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn get_slice_mut_unchecked(&mut self, start: usize, len: usize) -> &mut [T] {
         unsafe { &mut *self.get_slice_ptr_unchecked(start, len).as_ptr() }
@@ -1063,7 +1378,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn reserve(&mut self, additional: usize) -> Result<(), AllocError> {
         unsafe {
@@ -1078,42 +1393,227 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     ///
     /// - [`AllocError::Other("attempted to truncate a slice to a larger size")`] if
     ///   `len > self.initialized()`.
+    #[track_caller]
     pub fn truncate(&mut self, len: usize) -> Result<(), AllocError> {
         match len.cmp(&self.init) {
             Ordering::Greater => return Err(AllocError::Other(TRUNC_LGR)),
-            Ordering::Equal => unsafe {
-                slice_ptr_from_raw_parts(self.as_mut_ptr().add(len), self.init - len)
-                    .drop_in_place();
+            Ordering::Less => unsafe {
+                let tail = slice_ptr_from_raw_parts(self.as_mut_ptr().add(len), self.init - len);
                 self.init = len;
+                tail.drop_in_place();
             },
-            Ordering::Less => {}
+            Ordering::Equal => {}
         }
 
         Ok(())
     }
 
+    /// Removes the given range of initialized elements, returning them in an iterator.
+    ///
+    /// The elements after the range are shifted back when the iterator is dropped, even if not
+    /// all removed elements were yielded. The uninitialized tail of the buffer is left untouched.
+    ///
+    /// Returns `None` if the range is out of the bounds of the initialized buffer.
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Option<Drain<'_, T, A>> {
+        let (start, end) = range_in(&range, self.init)?;
+        Some(unsafe { Drain::new(self, start, end) })
+    }
+
+    /// Replaces the given range of initialized elements with the elements of `replace_with`,
+    /// returning the removed elements in an iterator.
+    ///
+    /// The replacement elements are written when [`Splice::finish`] is called or the iterator is
+    /// dropped, growing the buffer according to its [`GrowthPolicy`] if necessary.
+    ///
+    /// Returns `None` if the range is out of the bounds of the initialized buffer.
+    #[inline]
+    pub fn splice<R: RangeBounds<usize>, I: IntoIterator<Item = T>>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Option<Splice<'_, I::IntoIter, A>> {
+        Some(Splice::new(self.drain(range)?, replace_with.into_iter()))
+    }
+
+    /// Retains only the initialized elements for which `f` returns `true`, dropping the rest and
+    /// preserving the order of those kept.
+    #[inline]
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Retains only the initialized elements for which `f` returns `true`, dropping the rest and
+    /// preserving the order of those kept. `f` may mutate the elements it is given.
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        /// Closes the gap left by removed elements, even if `f` or a destructor panics.
+        struct RetainGuard<'b, T, A: Alloc> {
+            buf: &'b mut OwnedBuf<T, A>,
+            processed: usize,
+            deleted: usize,
+            original: usize,
+        }
+
+        impl<T, A: Alloc> Drop for RetainGuard<'_, T, A> {
+            fn drop(&mut self) {
+                if self.deleted != 0 {
+                    unsafe {
+                        let ptr = self.buf.buf.as_ptr();
+                        ptr.add(self.processed).copy_to(
+                            ptr.add(self.processed - self.deleted),
+                            self.original - self.processed,
+                        );
+                    }
+                }
+                self.buf.init = self.original - self.deleted;
+            }
+        }
+
+        let original = self.init;
+        // if `f` panics, the guard restores the count. if the guard is somehow skipped, we leak
+        // instead of double dropping.
+        self.init = 0;
+        let mut g = RetainGuard {
+            buf: self,
+            processed: 0,
+            deleted: 0,
+            original,
+        };
+
+        while g.processed != original {
+            let cur = unsafe { g.buf.buf.as_ptr().add(g.processed) };
+            if f(unsafe { &mut *cur }) {
+                if g.deleted != 0 {
+                    unsafe {
+                        cur.copy_to_nonoverlapping(cur.sub(g.deleted), 1);
+                    }
+                }
+                g.processed += 1;
+            } else {
+                g.processed += 1;
+                g.deleted += 1;
+                unsafe {
+                    cur.drop_in_place();
+                }
+            }
+        }
+    }
+
+    /// Removes all but the first of consecutive initialized elements for which `same_bucket`
+    /// returns `true`.
+    ///
+    /// `same_bucket` is given the element being checked first, and the last element kept second.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        /// Closes the gap left by removed elements, even if `same_bucket` or a destructor panics.
+        struct DedupGuard<'b, T, A: Alloc> {
+            buf: &'b mut OwnedBuf<T, A>,
+            read: usize,
+            write: usize,
+            original: usize,
+        }
+
+        impl<T, A: Alloc> Drop for DedupGuard<'_, T, A> {
+            fn drop(&mut self) {
+                unsafe {
+                    let ptr = self.buf.buf.as_ptr();
+                    ptr.add(self.read)
+                        .copy_to(ptr.add(self.write), self.original - self.read);
+                }
+                self.buf.init = self.write + self.original - self.read;
+            }
+        }
+
+        let original = self.init;
+        if original <= 1 {
+            return;
+        }
+        self.init = 0;
+        let mut g = DedupGuard {
+            buf: self,
+            read: 1,
+            write: 1,
+            original,
+        };
+
+        while g.read != original {
+            unsafe {
+                let ptr = g.buf.buf.as_ptr();
+                let cur = ptr.add(g.read);
+                let prev = ptr.add(g.write - 1);
+                if same_bucket(&mut *cur, &mut *prev) {
+                    g.read += 1;
+                    cur.drop_in_place();
+                } else {
+                    if g.read != g.write {
+                        cur.copy_to_nonoverlapping(ptr.add(g.write), 1);
+                    }
+                    g.write += 1;
+                    g.read += 1;
+                }
+            }
+        }
+    }
+
+    /// Removes all but the first of consecutive initialized elements which resolve to the same
+    /// key.
+    #[inline]
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive equal initialized elements.
+    #[inline]
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
     /// Shrinks the vector's capacity to fit only as many elements as it has.
     ///
     /// # Errors
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn shrink_to_fit(&mut self) -> Result<(), AllocError> {
         if self.init < self.size {
-            self.buf = unsafe {
-                self.alloc
-                    .shrink(
-                        self.buf.cast::<u8>(),
-                        // we were able to allocate with this earlier, so it is valid.
-                        Layout::from_size_align_unchecked(self.size * T::SZ, T::ALIGN),
-                        Layout::from_size_align_unchecked(self.init * T::SZ, T::ALIGN),
-                    )?
-                    .cast::<T>()
-            };
-            self.size = self.init;
+            // SAFETY: `self.init < self.size`.
+            unsafe {
+                self.shrink_exact(self.init)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shrinks the buffer to exactly `new_size` elements, deallocating it if `new_size` is `0`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure `self.init <= new_size <= self.size`.
+    #[track_caller]
+    unsafe fn shrink_exact(&mut self, new_size: usize) -> Result<(), AllocError> {
+        if T::IS_ZST {
+            self.size = new_size;
+            return Ok(());
         }
+        if new_size == 0 {
+            dealloc_n(self.alloc(), self.buf, self.size);
+            self.buf = NonNull::dangling();
+        } else {
+            self.buf = self
+                .alloc
+                .shrink(
+                    self.buf.cast::<u8>(),
+                    // we were able to allocate with this earlier, so it is valid.
+                    Layout::from_size_align_unchecked(self.size * T::SZ, T::ALIGN),
+                    Layout::from_size_align_unchecked(new_size * T::SZ, T::ALIGN),
+                )?
+                .cast::<T>();
+        }
+        self.size = new_size;
         Ok(())
     }
 
@@ -1123,7 +1623,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn expand_to_fit(&mut self, necessary_size: usize) -> Result<(), AllocError> {
         if self.size < necessary_size {
@@ -1134,6 +1634,46 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         Ok(())
     }
 
+    /// Grows the buffer to fit at least `required` elements, picking the new size using the
+    /// buffer's [`GrowthPolicy`] and adopting any extra usable space the allocator reports.
+    #[track_caller]
+    pub(crate) fn grow_amortized(&mut self, required: usize) -> Result<(), AllocError> {
+        if self.size >= required {
+            return Ok(());
+        } else if T::IS_ZST {
+            self.size = required;
+            return Ok(());
+        }
+
+        let mut new_size = self.growth.next_size(self.size, required);
+        let layout = match layout_or_sz_align::<T>(new_size) {
+            Ok(layout) => layout,
+            // the amortized size may overflow where the exact one wouldn't.
+            Err(_) if new_size != required => {
+                new_size = required;
+                layout_or_sz_align::<T>(new_size)
+                    .map_err(|(sz, align)| AllocError::LayoutError(sz, align))?
+            }
+            Err((sz, align)) => return Err(AllocError::LayoutError(sz, align)),
+        };
+
+        let block = if self.size == 0 {
+            self.alloc.alloc_sized(layout)?
+        } else {
+            unsafe {
+                self.alloc.grow_sized(
+                    self.buf.cast::<u8>(),
+                    // we were able to allocate with this earlier, so it is valid.
+                    Layout::from_size_align_unchecked(self.size * T::SZ, T::ALIGN),
+                    layout,
+                )?
+            }
+        };
+        self.buf = block.cast::<T>();
+        self.size = nonnull_slice_len(block) / T::SZ;
+        Ok(())
+    }
+
     /// Sets the size of the buffer to `new_size`.
     ///
     /// # Safety
@@ -1144,7 +1684,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub unsafe fn set_size_unchecked(&mut self, new_size: usize) -> Result<(), AllocError> {
         if new_size == self.size {
@@ -1170,8 +1710,12 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     /// Drops all initialized values and resets the count.
     #[inline]
     pub fn clear(&mut self) {
+        let len = self.init;
+        // reset first, so a panicking destructor can't make the elements get dropped again.
         self.init = 0;
-        self.clear_inner();
+        unsafe {
+            slice_ptr_from_raw_parts(self.buf.as_ptr(), len).drop_in_place();
+        }
     }
 
     #[inline]
@@ -1182,7 +1726,7 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     }
 
     /// Destructor to drop all initialized elements and deallocate the buffer.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn drop_and_dealloc(self) {
         if self.buf != NonNull::dangling() {
@@ -1195,20 +1739,20 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
 
     /// Destructor to drop all initialized elements, zero the allocated memory, and deallocate the
     /// buffer.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn drop_zero_and_dealloc(self) {
         if self.buf != NonNull::dangling() {
             unsafe {
                 self.clear_inner();
-                self.buf.as_ptr().write_bytes(0, self.size);
+                secure_zero(self.buf.as_ptr(), self.size);
                 dealloc_n(self.alloc(), self.buf, self.size);
             }
         }
     }
 
     /// Drops all initialized elements and deallocates the buffer.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn reset(&mut self) {
         if self.buf != NonNull::dangling() {
@@ -1223,13 +1767,13 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
     }
 
     /// Drops all initialized elements, zeroes allocated memory, and deallocates the buffer.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn reset_zero(&mut self) {
         if self.buf != NonNull::dangling() {
             unsafe {
                 self.clear_inner();
-                self.buf.as_ptr().write_bytes(0, self.size);
+                secure_zero(self.buf.as_ptr(), self.size);
                 dealloc_n(self.alloc(), self.buf, self.size);
                 self.init = 0;
                 self.size = 0;
@@ -1365,36 +1909,181 @@ impl<T, A: Alloc> OwnedBuf<T, A> {
         }
     }
 
-    /// Placeholder docs
-    #[allow(clippy::missing_errors_doc)]
-    // TODO: make sure this works
+    /// Converts the buffer into a buffer of `N`-element chunks, reusing the allocation.
+    ///
+    /// Trailing initialized elements which don't fill a whole chunk are dropped, and the buffer
+    /// is shrunk to a multiple of `N` elements if necessary.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(Soft(self))` if `N == 0`.
+    /// - `Err(Hard((self, alloc_err)))` if shrinking the buffer fails. The trailing elements will
+    ///   already have been dropped.
+    ///
+    /// `alloc_err` may be:
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    #[allow(clippy::type_complexity)]
+    #[track_caller]
     pub fn into_chunks<const N: usize>(
         mut self,
-    ) -> Result<OwnedBuf<[T; N], A>, VariableError<(), AllocError>> {
+    ) -> Result<OwnedBuf<[T; N], A>, VariableError<OwnedBuf<T, A>, (OwnedBuf<T, A>, AllocError)>>
+    {
         if N == 0 {
-            return Err(Soft(()));
+            return Err(Soft(self));
         }
 
-        let init = self.init;
-        let size = self.size;
+        let _ = self.truncate(self.init - self.init % N);
 
-        if let Err(e) = self.truncate(init - init % N) {
-            return Err(Hard(e));
+        let usable_size = self.size - self.size % N;
+        if usable_size != self.size {
+            // SAFETY: after the truncation, `self.init <= usable_size`.
+            if let Err(e) = unsafe { self.shrink_exact(usable_size) } {
+                return Err(Hard((self, e)));
+            }
         }
 
-        let usable_size = size % N;
-        if !T::IS_ZST && usable_size != 0 {
-            // SAFETY: after the truncation, self.init will be less than usable_size.
-            unsafe {
-                if let Err(e) = self.set_size_unchecked(usable_size) {
-                    return Err(Hard(e));
-                }
+        let growth = self.growth;
+        let (buf, init, size, a) = self.into_raw_parts();
+        Ok(
+            unsafe { OwnedBuf::from_raw_parts(buf.cast(), init / N, size / N, a) }
+                .with_growth_policy(growth),
+        )
+    }
+
+    /// Converts the buffer into a [`HeapVal`] holding an array of exactly `N` elements, reusing
+    /// the allocation.
+    ///
+    /// The buffer is shrunk to `N` elements if necessary.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(Soft(self))` if the buffer doesn't have exactly `N` initialized elements, or
+    ///   `[T; N]` is zero-sized.
+    /// - `Err(Hard((self, alloc_err)))` if shrinking the buffer fails.
+    ///
+    /// `alloc_err` may be:
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    #[allow(clippy::type_complexity)]
+    #[track_caller]
+    pub fn into_array<const N: usize>(
+        mut self,
+    ) -> Result<HeapVal<[T; N], A>, VariableError<OwnedBuf<T, A>, (OwnedBuf<T, A>, AllocError)>>
+    {
+        if self.init != N || <[T; N]>::IS_ZST {
+            return Err(Soft(self));
+        }
+        if self.size != N {
+            // SAFETY: `self.init == N`.
+            if let Err(e) = unsafe { self.shrink_exact(N) } {
+                return Err(Hard((self, e)));
             }
         }
 
         let (buf, _, _, a) = self.into_raw_parts();
+        Ok(unsafe { HeapVal::from_raw_in(buf.cast(), a) })
+    }
 
-        Ok(unsafe { OwnedBuf::from_raw_parts(buf.cast(), init / N, size / N, a) })
+    /// Reinterprets the initialized elements as elements of type `U`.
+    ///
+    /// The allocation is reused if `T` and `U` have the same alignment and the buffer's size in
+    /// bytes is a multiple of `U`'s size. Otherwise, the initialized elements are copied into a
+    /// new allocation which fits them exactly.
+    ///
+    /// # Errors
+    ///
+    /// - `Err(Soft(self))` if exactly one of `T` and `U` is zero-sized, or the initialized
+    ///   elements' size in bytes isn't a multiple of `U`'s size.
+    /// - `Err(Hard((self, alloc_err)))` if reallocation is necessary and fails.
+    ///
+    /// `alloc_err` may be:
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the bytes of the initialized elements are valid for `U`, and that
+    /// dropping them as `U` is sound.
+    #[allow(clippy::type_complexity)]
+    #[track_caller]
+    pub unsafe fn cast<U>(
+        self,
+    ) -> Result<OwnedBuf<U, A>, VariableError<OwnedBuf<T, A>, (OwnedBuf<T, A>, AllocError)>> {
+        let growth = self.growth;
+        if T::IS_ZST || U::IS_ZST {
+            if !(T::IS_ZST && U::IS_ZST) {
+                return Err(Soft(self));
+            }
+            let (buf, init, size, a) = self.into_raw_parts();
+            return Ok(
+                OwnedBuf::from_raw_parts(buf.cast(), init, size, a).with_growth_policy(growth)
+            );
+        }
+
+        let init_bytes = self.init * T::SZ;
+        if init_bytes % U::SZ != 0 {
+            return Err(Soft(self));
+        }
+        let size_bytes = self.size * T::SZ;
+
+        if T::ALIGN == U::ALIGN && size_bytes % U::SZ == 0 {
+            let (buf, _, _, a) = self.into_raw_parts();
+            return Ok(OwnedBuf::from_raw_parts(
+                buf.cast(),
+                init_bytes / U::SZ,
+                size_bytes / U::SZ,
+                a,
+            )
+            .with_growth_policy(growth));
+        }
+
+        let new_init = init_bytes / U::SZ;
+        let new_buf = if new_init == 0 {
+            NonNull::<U>::dangling()
+        } else {
+            let layout = match layout_or_sz_align::<U>(new_init) {
+                Ok(layout) => layout,
+                Err((sz, align)) => return Err(Hard((self, AllocError::LayoutError(sz, align)))),
+            };
+            match self.alloc.alloc(layout) {
+                Ok(ptr) => ptr.cast::<U>(),
+                Err(e) => return Err(Hard((self, e))),
+            }
+        };
+        let (buf, _, size, a) = self.into_raw_parts();
+        buf.cast::<u8>()
+            .as_ptr()
+            .copy_to_nonoverlapping(new_buf.cast::<u8>().as_ptr(), init_bytes);
+        if size != 0 {
+            dealloc_n(&a, buf, size);
+        }
+        Ok(OwnedBuf::from_raw_parts(new_buf, new_init, new_init, a).with_growth_policy(growth))
+    }
+}
+
+impl<T, A: Alloc, const N: usize> OwnedBuf<[T; N], A> {
+    /// Converts a buffer of `N`-element arrays into a flat buffer of `T`, reusing the allocation.
+    ///
+    /// This reverses [`into_chunks`](OwnedBuf::into_chunks).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized and the flattened length overflows `usize`.
+    #[must_use]
+    #[track_caller]
+    pub fn into_flattened(self) -> OwnedBuf<T, A> {
+        let growth = self.growth;
+        let (buf, init, size, a) = self.into_raw_parts();
+        let (init, size) = if T::IS_ZST {
+            (
+                init.checked_mul(N)
+                    .expect("`OwnedBuf::into_flattened` length overflow"),
+                0,
+            )
+        } else {
+            // the allocation's size in bytes can't overflow, so this can't either.
+            (init * N, size * N)
+        };
+        unsafe { OwnedBuf::from_raw_parts(buf.cast(), init, size, a) }.with_growth_policy(growth)
     }
 }
 
@@ -1408,11 +2097,13 @@ impl<T, A: Alloc + Debug> Debug for OwnedBuf<T, A> {
             .field("init", &self.init)
             .field("size", &self.size)
             .field("alloc", &self.alloc)
+            .field("growth", &self.growth)
             .finish()
     }
 }
 
 impl<T, A: Alloc + Default> Default for OwnedBuf<T, A> {
+    #[track_caller]
     #[inline]
     fn default() -> OwnedBuf<T, A> {
         OwnedBuf::new_unallocated_in(A::default())
@@ -1620,6 +2311,7 @@ impl<T> From<OwnedBuf<T>> for alloc::vec::Vec<T> {
 #[cfg(feature = "nightly")]
 //noinspection RsUnnecessaryQualifications
 impl<T, A: Alloc + alloc::alloc::Allocator> From<alloc::vec::Vec<T, A>> for OwnedBuf<T, A> {
+    #[track_caller]
     fn from(vec: alloc::vec::Vec<T, A>) -> OwnedBuf<T, A> {
         let (buf, init, size, a) = vec.into_parts_with_alloc();
         unsafe { OwnedBuf::from_raw_parts(buf, init, size, a) }
@@ -1639,12 +2331,14 @@ impl<T, A: Alloc + alloc::alloc::Allocator> From<alloc::vec::Vec<T, A>> for Owne
 // }
 
 impl<T: Clone, A: Alloc + Clone> Clone for OwnedBuf<T, A> {
+    #[track_caller]
     fn clone(&self) -> OwnedBuf<T, A> {
         Buf::from(self)
             .clone_into_owned_in(self.alloc.clone())
             .expect("`OwnedBuf::clone` failed")
     }
 
+    #[track_caller]
     fn clone_from(&mut self, source: &OwnedBuf<T, A>) {
         source
             .as_slice()
@@ -1655,6 +2349,33 @@ impl<T: Clone, A: Alloc + Clone> Clone for OwnedBuf<T, A> {
 
 trait SpecCi<T, A: Alloc> {
     fn clone_into_ob(&self, target: &mut OwnedBuf<T, A>) -> Result<(), AllocError>;
+    fn extend_ob(&self, target: &mut OwnedBuf<T, A>) -> Result<(), AllocError>;
+    unsafe fn insert_ob(&self, target: &mut OwnedBuf<T, A>, idx: usize);
+}
+
+/// Closes the gap left by [`SpecCi::insert_ob`] if a clone panics.
+struct InsertGuard<'b, T, A: Alloc> {
+    buf: &'b mut OwnedBuf<T, A>,
+    idx: usize,
+    written: usize,
+    gap: usize,
+    tail: usize,
+}
+
+impl<T, A: Alloc> Drop for InsertGuard<'_, T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            let dst = self.buf.buf.as_ptr().add(self.idx + self.written);
+            if self.written != self.gap {
+                self.buf
+                    .buf
+                    .as_ptr()
+                    .add(self.idx + self.gap)
+                    .copy_to(dst, self.tail);
+            }
+        }
+        self.buf.init = self.idx + self.written + self.tail;
+    }
 }
 
 macro_rules! spec_ci_impl {
@@ -1665,16 +2386,52 @@ macro_rules! spec_ci_impl {
                 &self,
                 target: &mut OwnedBuf<T, A>
             ) -> Result<(), AllocError> {
-                let _ = target.truncate(self.len());
+                if target.len() > self.len() {
+                    let _ = target.truncate(self.len());
+                }
 
-                 let (init, tail) = self.split_at(target.len());
+                let (init, tail) = self.split_at(target.len());
 
                 target.clone_from_slice(init);
-                // temporary solution until extend_from_slice is implemented.
-                target.try_insert_slice_grow(0, OwnedBuf::<T>::from(tail))
-                    .expect("`OwnedBuf::clone_from`: necessary grow failed");
+                target.extend_from_slice(tail)
+            }
+
+            #[inline]
+            $($extra_token)? fn extend_ob(
+                &self,
+                target: &mut OwnedBuf<T, A>
+            ) -> Result<(), AllocError> {
+                target.grow_amortized(target.required(self.len())?)?;
+                for elem in self {
+                    // the count is bumped per element so a panicking clone can't expose
+                    // uninitialized memory.
+                    unsafe {
+                        target.init_next_unchecked(elem.clone());
+                    }
+                }
                 Ok(())
             }
+
+            #[inline]
+            $($extra_token)? unsafe fn insert_ob(&self, target: &mut OwnedBuf<T, A>, idx: usize) {
+                let tail = target.init - idx;
+                let dst = target.buf.as_ptr().add(idx);
+                dst.copy_to(dst.add(self.len()), tail);
+                // the tail is outside of the initialized count until the guard puts it back.
+                target.init = idx;
+
+                let mut g = InsertGuard {
+                    buf: target,
+                    idx,
+                    written: 0,
+                    gap: self.len(),
+                    tail,
+                };
+                for elem in self {
+                    dst.add(g.written).write(elem.clone());
+                    g.written += 1;
+                }
+            }
         }
     }
 }
@@ -1687,20 +2444,52 @@ spec_ci_impl!(default);
 
 #[cfg(feature = "specialization")]
 impl<T: Copy, A: Alloc> SpecCi<T, A> for [T] {
+    #[track_caller]
     #[inline]
     fn clone_into_ob(&self, target: &mut OwnedBuf<T, A>) -> Result<(), AllocError> {
         target.clear();
-        match target.try_insert_slice_grow(0, OwnedBuf::<T>::from(self)) {
-            Ok(()) => Ok(()),
-            Err(e) => match e {
-                Hard((buf, e)) => {
-                    buf.drop_and_dealloc();
-                    Err(e)
-                }
-                // 0 can't be oob, so this is safe
-                Soft(_) => unsafe { core::hint::unreachable_unchecked() },
-            },
-        }
+        self.extend_ob(target)
+    }
+
+    #[track_caller]
+    #[inline]
+    fn extend_ob(&self, target: &mut OwnedBuf<T, A>) -> Result<(), AllocError> {
+        target.extend_from_copy_slice(self)
+    }
+
+    #[inline]
+    unsafe fn insert_ob(&self, target: &mut OwnedBuf<T, A>, idx: usize) {
+        let dst = target.buf.as_ptr().add(idx);
+        dst.copy_to(dst.add(self.len()), target.init - idx);
+        self.as_ptr().copy_to_nonoverlapping(dst, self.len());
+        target.init += self.len();
+    }
+}
+
+impl<T, A: Alloc> Extend<T> for OwnedBuf<T, A> {
+    #[track_caller]
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.try_extend(iter).expect("`OwnedBuf::extend` failed");
+    }
+}
+
+impl<'t, T: Copy + 't, A: Alloc> Extend<&'t T> for OwnedBuf<T, A> {
+    #[track_caller]
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'t T>>(&mut self, iter: I) {
+        self.try_extend(iter.into_iter().copied())
+            .expect("`OwnedBuf::extend` failed");
+    }
+}
+
+impl<T, A: Alloc + Default> FromIterator<T> for OwnedBuf<T, A> {
+    #[track_caller]
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OwnedBuf<T, A> {
+        let mut buf = OwnedBuf::new_unallocated_in(A::default());
+        buf.extend(iter);
+        buf
     }
 }
 
@@ -1775,10 +2564,19 @@ impl<'s, T, A: Alloc> IntoIterator for &'s mut OwnedBuf<T, A> {
     }
 }
 
-// impl<T, A: Alloc> IntoIterator for OwnedBuf<T, A> {
-//     type Item = T;
-//     type IntoIter = OwnedIter<T, A>;
-// }
+impl<T, A: Alloc> IntoIterator for OwnedBuf<T, A> {
+    type Item = T;
+    type IntoIter = OwnedIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> OwnedIter<T, A> {
+        OwnedIter::new(self)
+    }
+}
+
+// SAFETY: `OwnedBuf` owns its elements and allocator, so it's as thread-safe as they are.
+unsafe impl<T: Send, A: Alloc + Send> Send for OwnedBuf<T, A> {}
+unsafe impl<T: Sync, A: Alloc + Sync> Sync for OwnedBuf<T, A> {}
 
 #[cfg(all(feature = "drop_for_owned", not(feature = "zero_drop_for_owned")))]
 impl<T, A: Alloc> Drop for OwnedBuf<T, A> {
@@ -1943,7 +2741,7 @@ impl<T> Buf<'_, T> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn clone_into_owned(&self) -> Result<OwnedBuf<T>, AllocError>
     where
@@ -1959,6 +2757,7 @@ impl<T> Buf<'_, T> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    #[track_caller]
     #[inline]
     pub fn copy_into_owned(&self) -> Result<OwnedBuf<T>, AllocError>
     where
@@ -1978,6 +2777,7 @@ impl<T> Buf<'_, T> {
     /// # Safety
     ///
     /// The caller must ensure the performed copying of elements is safe.
+    #[track_caller]
     #[inline]
     pub unsafe fn copy_into_owned_unchecked(&self) -> Result<OwnedBuf<T>, AllocError> {
         self.copy_into_owned_in_unchecked(DefaultAlloc)
@@ -1990,7 +2790,7 @@ impl<T> Buf<'_, T> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn clone_into_owned_in<A: Alloc>(&self, alloc: A) -> Result<OwnedBuf<T, A>, AllocError>
     where
@@ -2011,6 +2811,7 @@ impl<T> Buf<'_, T> {
             init: self.init,
             size,
             alloc,
+            growth: GrowthPolicy::Double,
             _marker: PhantomData,
         })
     }
@@ -2022,6 +2823,7 @@ impl<T> Buf<'_, T> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    #[track_caller]
     #[inline]
     pub fn copy_into_owned_in<A: Alloc>(&self, alloc: A) -> Result<OwnedBuf<T, A>, AllocError>
     where
@@ -2041,6 +2843,7 @@ impl<T> Buf<'_, T> {
     /// # Safety
     ///
     /// The caller must ensure the performed copying of elements is safe.
+    #[track_caller]
     #[inline]
     pub unsafe fn copy_into_owned_in_unchecked<A: Alloc>(
         &self,
@@ -2057,6 +2860,7 @@ impl<T> Buf<'_, T> {
             init: self.init,
             size,
             alloc,
+            growth: GrowthPolicy::Double,
             _marker: PhantomData,
         })
     }
@@ -2127,7 +2931,7 @@ impl<T> Buf<'static, T> {
     /// # Safety
     ///
     /// The contained elements must be unowned and allocated using `alloc`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub const unsafe fn into_owned<A: Alloc>(self, alloc: A) -> OwnedBuf<T, A> {
         let Buf { init, buf: elems } = self;
@@ -2136,6 +2940,7 @@ impl<T> Buf<'static, T> {
             init,
             size: elems.len(),
             alloc,
+            growth: GrowthPolicy::Double,
             _marker: PhantomData,
         }
     }
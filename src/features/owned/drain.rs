@@ -0,0 +1,342 @@
+use crate::{
+    error::ArithOp,
+    helpers::{nonnull_slice_from_raw_parts, slice_ptr_from_raw_parts},
+    owned::OwnedBuf,
+    Alloc, AllocError,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+    ops::{Bound, RangeBounds},
+    ptr::NonNull,
+};
+
+/// Converts `range` into a `start..end` pair, returning `None` if it isn't within `0..len`.
+pub(crate) fn range_in<R: RangeBounds<usize>>(range: &R, len: usize) -> Option<(usize, usize)> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.checked_add(1)?,
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    if start > end || end > len {
+        None
+    } else {
+        Some((start, end))
+    }
+}
+
+/// A draining iterator over a range of an [`OwnedBuf`]'s initialized elements.
+///
+/// While this iterator is alive, the buffer's initialized count only covers the elements before
+/// the drained range. When it is dropped, any elements which were not yielded are dropped and the
+/// elements after the range are shifted back to close the gap. If this iterator is leaked, the
+/// elements in and after the range are leaked too.
+pub struct Drain<'b, T, A: Alloc> {
+    /// The buffer being drained.
+    buf: &'b mut OwnedBuf<T, A>,
+    /// The index of the next element to yield from the front.
+    idx: usize,
+    /// The index one past the next element to yield from the back.
+    end: usize,
+    /// The index of the first element after the drained range.
+    tail_start: usize,
+    /// The number of elements after the drained range.
+    tail_len: usize,
+}
+
+impl<'b, T, A: Alloc> Drain<'b, T, A> {
+    /// Creates a new draining iterator over `start..end` of `buf`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure `start <= end <= buf.initialized()`.
+    #[inline]
+    pub(crate) unsafe fn new(
+        buf: &'b mut OwnedBuf<T, A>,
+        start: usize,
+        end: usize,
+    ) -> Drain<'b, T, A> {
+        let tail_len = buf.initialized() - end;
+        buf.set_initialized(start);
+        Drain {
+            buf,
+            idx: start,
+            end,
+            tail_start: end,
+            tail_len,
+        }
+    }
+
+    /// Gets a slice of the drained elements which have not been yielded yet.
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { &*self.remaining().as_ptr() }
+    }
+
+    /// Gets a pointer to the drained elements which have not been yielded yet.
+    #[inline]
+    fn remaining(&self) -> NonNull<[T]> {
+        nonnull_slice_from_raw_parts(
+            unsafe { NonNull::new_unchecked(self.buf.as_nonnull().as_ptr().add(self.idx)) },
+            self.end - self.idx,
+        )
+    }
+
+    /// Writes elements from `iter` into the gap between the initialized elements and the tail.
+    ///
+    /// Returns `false` if `iter` ran out before the gap was filled.
+    fn fill_gap<I: Iterator<Item = T>>(&mut self, iter: &mut I) -> bool {
+        while self.buf.initialized() < self.tail_start {
+            match iter.next() {
+                Some(val) => unsafe {
+                    let init = self.buf.initialized();
+                    self.buf.as_mut_ptr().add(init).write(val);
+                    self.buf.set_initialized(init + 1);
+                },
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Moves the tail back by `additional` elements, growing the buffer if necessary.
+    #[track_caller]
+    fn move_tail(&mut self, additional: usize) -> Result<(), AllocError> {
+        let required = match (self.tail_start + self.tail_len).checked_add(additional) {
+            Some(r) => r,
+            None => {
+                return Err(AllocError::ArithmeticOverflow(
+                    self.tail_start + self.tail_len,
+                    ArithOp::Add,
+                    additional,
+                ))
+            }
+        };
+        self.buf.grow_amortized(required)?;
+
+        let new_start = self.tail_start + additional;
+        unsafe {
+            let ptr = self.buf.as_mut_ptr();
+            ptr.add(self.tail_start)
+                .copy_to(ptr.add(new_start), self.tail_len);
+        }
+        self.tail_start = new_start;
+        Ok(())
+    }
+}
+
+impl<T, A: Alloc> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.idx == self.end {
+            return None;
+        }
+        let val = unsafe { self.buf.as_ptr().add(self.idx).read() };
+        self.idx += 1;
+        Some(val)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Alloc> DoubleEndedIterator for Drain<'_, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.idx == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(unsafe { self.buf.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<T, A: Alloc> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: Alloc> FusedIterator for Drain<'_, T, A> {}
+
+impl<T: Debug, A: Alloc> Debug for Drain<'_, T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Alloc> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        /// Shifts the tail back even if dropping a remaining element panics.
+        struct TailGuard<'r, 'b, T, A: Alloc>(&'r mut Drain<'b, T, A>);
+
+        impl<T, A: Alloc> Drop for TailGuard<'_, '_, T, A> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                unsafe {
+                    let init = drain.buf.initialized();
+                    if drain.tail_start != init {
+                        let ptr = drain.buf.as_mut_ptr();
+                        ptr.add(drain.tail_start)
+                            .copy_to(ptr.add(init), drain.tail_len);
+                    }
+                    drain.buf.set_initialized(init + drain.tail_len);
+                }
+            }
+        }
+
+        let remaining = unsafe { self.buf.as_mut_ptr().add(self.idx) };
+        let cnt = self.end - self.idx;
+        self.idx = self.end;
+        let _guard = TailGuard(self);
+        unsafe {
+            slice_ptr_from_raw_parts(remaining, cnt).drop_in_place();
+        }
+    }
+}
+
+/// A splicing iterator for an [`OwnedBuf`].
+///
+/// This yields the removed elements like a [`Drain`]. The replacement elements are written into
+/// the buffer by [`finish`](Splice::finish) or, failing that, when this iterator is dropped.
+///
+/// # Panics
+///
+/// Dropping this iterator without calling [`finish`](Splice::finish) panics if the buffer has to
+/// grow and allocation fails.
+pub struct Splice<'b, I: Iterator, A: Alloc> {
+    /// The drain over the replaced range.
+    drain: Drain<'b, I::Item, A>,
+    /// The replacement elements.
+    replace_with: I,
+    /// Whether the replacement elements have been written.
+    done: bool,
+}
+
+impl<'b, I: Iterator, A: Alloc> Splice<'b, I, A> {
+    /// Creates a new splicing iterator from a drain and a replacement iterator.
+    #[inline]
+    pub(crate) fn new(drain: Drain<'b, I::Item, A>, replace_with: I) -> Splice<'b, I, A> {
+        Splice {
+            drain,
+            replace_with,
+            done: false,
+        }
+    }
+
+    /// Drops any removed elements which were not yielded and writes the replacement elements
+    /// into the buffer.
+    ///
+    /// # Errors
+    ///
+    /// If the buffer had to grow:
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::LayoutError`] if the computed layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the required size overflows.
+    ///
+    /// On error, the buffer keeps every replacement element written so far.
+    #[track_caller]
+    #[inline]
+    pub fn finish(mut self) -> Result<(), AllocError> {
+        self.fill()
+    }
+
+    #[track_caller]
+    fn fill(&mut self) -> Result<(), AllocError> {
+        self.done = true;
+        self.drain.by_ref().for_each(drop);
+
+        if !self.drain.fill_gap(&mut self.replace_with) {
+            return Ok(());
+        }
+
+        // the size hint's lower bound is a good guess for how much more space we need.
+        let (lower, _) = self.replace_with.size_hint();
+        if lower > 0 {
+            self.drain.move_tail(lower)?;
+            if !self.drain.fill_gap(&mut self.replace_with) {
+                return Ok(());
+            }
+        }
+
+        // collect whatever is left so the tail only has to move once more.
+        let mut rest = OwnedBuf::<I::Item>::new_unallocated();
+        let res = self.fill_rest(&mut rest);
+        rest.reset();
+        res
+    }
+
+    #[track_caller]
+    fn fill_rest(&mut self, rest: &mut OwnedBuf<I::Item>) -> Result<(), AllocError> {
+        for val in self.replace_with.by_ref() {
+            rest.init_next_grow(val)?;
+        }
+        let cnt = rest.initialized();
+        if cnt == 0 {
+            return Ok(());
+        }
+        self.drain.move_tail(cnt)?;
+        unsafe {
+            let init = self.drain.buf.initialized();
+            rest.as_ptr()
+                .copy_to_nonoverlapping(self.drain.buf.as_mut_ptr().add(init), cnt);
+            rest.set_initialized(0);
+            self.drain.buf.set_initialized(init + cnt);
+        }
+        Ok(())
+    }
+}
+
+impl<I: Iterator, A: Alloc> Iterator for Splice<'_, I, A> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.drain.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<I: Iterator, A: Alloc> DoubleEndedIterator for Splice<'_, I, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<I::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<I: Iterator, A: Alloc> ExactSizeIterator for Splice<'_, I, A> {}
+
+impl<I: Iterator, A: Alloc> Debug for Splice<'_, I, A>
+where
+    I: Debug,
+    I::Item: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Splice")
+            .field("drain", &self.drain)
+            .field("replace_with", &self.replace_with)
+            .finish()
+    }
+}
+
+impl<I: Iterator, A: Alloc> Drop for Splice<'_, I, A> {
+    fn drop(&mut self) {
+        if !self.done {
+            if let Err(e) = self.fill() {
+                panic!("failed to splice replacement elements: {e}");
+            }
+        }
+    }
+}
@@ -1,43 +1,138 @@
-// TODO: finish this
-// use core::{
-//     marker::PhantomData,
-//     mem::ManuallyDrop,
-//     ptr::NonNull
-// };
-// use crate::{
-//     Alloc,
-//     type_props::SizedProps
-// };
-//
-// pub struct OwnedIter<T, A: Alloc> {
-//     buf: NonNull<T>,
-//     _marker: PhantomData<T>,
-//     size: usize,
-//     alloc: ManuallyDrop<A>,
-//     ptr: NonNull<T>,
-//     end: *const T,
-// }
-//
-// impl<T, A: Alloc> Iterator for OwnedIter<T, A> {
-//     type Item = T;
-//
-//     fn next(&mut self) -> Option<T> {
-//         let ptr = if T::IS_ZST {
-//             if self.ptr.as_ptr() == self.end.cast_mut() {
-//                 return None;
-//             }
-//
-//             self.end = self.end.wrapping_byte_sub(1);
-//             self.ptr
-//         } else {
-//             if self.ptr.as_ptr() == self.end.cast_mut() {
-//                 return None;
-//             }
-//
-//             let old = self.ptr;
-//             self.ptr = unsafe { old.add(1) };
-//             old
-//         };
-//         Some(unsafe { ptr.read() })
-//     }
-// }
+use crate::{
+    helpers::{dealloc_n, nonnull_slice_from_raw_parts},
+    owned::OwnedBuf,
+    Alloc,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+/// An iterator which moves the initialized elements out of an [`OwnedBuf`].
+///
+/// Any elements which were not yielded are dropped, and the buffer is deallocated using `A`, when
+/// this iterator is dropped.
+pub struct OwnedIter<T, A: Alloc> {
+    /// The buffer.
+    buf: NonNull<T>,
+    /// The length of the entire buffer.
+    size: usize,
+    /// The index of the next element to yield from the front.
+    start: usize,
+    /// The index one past the next element to yield from the back.
+    end: usize,
+    /// The allocator.
+    alloc: A,
+    _marker: PhantomData<T>,
+}
+
+impl<T, A: Alloc> OwnedIter<T, A> {
+    /// Creates a new iterator over the initialized elements of `buf`.
+    #[inline]
+    pub(crate) fn new(buf: OwnedBuf<T, A>) -> OwnedIter<T, A> {
+        let (buf, init, size, alloc) = buf.into_raw_parts();
+        OwnedIter {
+            buf,
+            size,
+            start: 0,
+            end: init,
+            alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets a reference to the contained allocator.
+    #[inline]
+    pub const fn alloc(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Gets a slice of the elements which have not been yielded yet.
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { &*self.remaining().as_ptr() }
+    }
+
+    /// Gets a mutable slice of the elements which have not been yielded yet.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { &mut *self.remaining().as_ptr() }
+    }
+
+    /// Gets a pointer to the elements which have not been yielded yet.
+    #[inline]
+    fn remaining(&self) -> NonNull<[T]> {
+        nonnull_slice_from_raw_parts(
+            unsafe { NonNull::new_unchecked(self.buf.as_ptr().add(self.start)) },
+            self.end - self.start,
+        )
+    }
+}
+
+impl<T, A: Alloc> Iterator for OwnedIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        let val = unsafe { self.buf.as_ptr().add(self.start).read() };
+        self.start += 1;
+        Some(val)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, A: Alloc> DoubleEndedIterator for OwnedIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(unsafe { self.buf.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<T, A: Alloc> ExactSizeIterator for OwnedIter<T, A> {}
+
+impl<T, A: Alloc> FusedIterator for OwnedIter<T, A> {}
+
+impl<T: Debug, A: Alloc> Debug for OwnedIter<T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedIter").field(&self.as_slice()).finish()
+    }
+}
+
+// SAFETY: `OwnedIter` owns the remaining elements and the allocator, like `OwnedBuf`.
+unsafe impl<T: Send, A: Alloc + Send> Send for OwnedIter<T, A> {}
+unsafe impl<T: Sync, A: Alloc + Sync> Sync for OwnedIter<T, A> {}
+
+impl<T, A: Alloc> Drop for OwnedIter<T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            self.remaining().as_ptr().drop_in_place();
+            if self.buf != NonNull::dangling() {
+                #[cfg(feature = "zero_drop_for_owned")]
+                {
+                    crate::helpers::secure_zero(self.buf.as_ptr(), self.size);
+                }
+                dealloc_n(&self.alloc, self.buf, self.size);
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 // TODO: make this align with the MSRV
 
 mod buf;
+mod drain;
 mod val;
-pub(crate) mod into_iter;
-// use into_iter::OwnedIter;
+mod into_iter;
 
 pub use buf::*;
+pub use drain::*;
+pub use into_iter::*;
 pub use val::*;
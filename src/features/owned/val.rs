@@ -1,6 +1,11 @@
 #![allow(clippy::missing_errors_doc, missing_docs)]
 
-use crate::{error::AllocError, helpers::alloc_write, type_props::PtrProps, Alloc, DefaultAlloc};
+use crate::{
+    error::AllocError,
+    helpers::{alloc_write, secure_zero},
+    type_props::PtrProps,
+    Alloc, DefaultAlloc,
+};
 use core::{
     borrow::{Borrow, BorrowMut},
     cmp::Ordering,
@@ -24,12 +29,14 @@ pub struct HeapVal<T: ?Sized, A: Alloc = DefaultAlloc> {
 
 impl<T> HeapVal<T> {
     /// Constructs a new [`HeapVal`] with the given value in the default allocator.
+    #[track_caller]
     #[inline]
     pub fn new(val: T) -> Result<HeapVal<T>, AllocError> {
         HeapVal::new_in(val, DefaultAlloc)
     }
 
     // todo: other variants which this just calls
+    #[track_caller]
     pub fn clone_from_ref(val: &T) -> Result<Self, AllocError>
     where
         T: Clone,
@@ -46,6 +53,7 @@ impl<T: ?Sized> HeapVal<T> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `val.size() == 0`.
+    #[track_caller]
     #[inline]
     pub fn copy_from_ref(val: &T) -> Result<HeapVal<T>, AllocError>
     where
@@ -65,6 +73,7 @@ impl<T: ?Sized> HeapVal<T> {
     /// # Safety
     ///
     /// The caller must ensure `val` points to a valid `T`.
+    #[track_caller]
     #[inline]
     pub unsafe fn copy_from_ptr(val: *const T) -> Result<HeapVal<T>, AllocError>
     where
@@ -88,11 +97,13 @@ impl<T: ?Sized> HeapVal<T> {
 
 impl<T, A: Alloc> HeapVal<T, A> {
     /// Constructs a new [`HeapVal`] with the given value, in the given allocator.
+    #[track_caller]
     #[inline]
     pub fn new_in(val: T, alloc: A) -> Result<HeapVal<T, A>, AllocError> {
         Ok(unsafe { HeapVal::from_raw_in(alloc_write(&alloc, val)?, alloc) })
     }
 
+    #[track_caller]
     pub fn clone_from_ref_in(val: &T, alloc: A) -> Result<HeapVal<T, A>, AllocError>
     where
         T: Clone,
@@ -108,12 +119,14 @@ impl<T, A: Alloc> HeapVal<T, A> {
 }
 
 impl<T: Default, A: Alloc + Default> Default for HeapVal<T, A> {
+    #[track_caller]
     fn default() -> HeapVal<T, A> {
         HeapVal::new_in(T::default(), A::default()).expect("`HeapVal::default()` allocation failed")
     }
 }
 
 impl<T: Clone, A: Alloc + Clone> Clone for HeapVal<T, A> {
+    #[track_caller]
     fn clone(&self) -> HeapVal<T, A> {
         HeapVal::clone_from_ref_in(self.as_ref(), self.alloc.clone())
             .expect("`HeapVal::clone()` allocation failed")
@@ -127,6 +140,7 @@ impl<T: Clone, A: Alloc + Clone> Clone for HeapVal<T, A> {
 // }
 
 impl<T, A: Alloc + Default> From<T> for HeapVal<T, A> {
+    #[track_caller]
     fn from(val: T) -> HeapVal<T, A> {
         HeapVal::new_in(val, A::default()).expect("`HeapVal::<From<T>>::from()` allocation failed")
     }
@@ -141,6 +155,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `val.size() == 0`.
+    #[track_caller]
     #[inline]
     pub fn copy_from_ref_in(val: &T, alloc: A) -> Result<HeapVal<T, A>, AllocError>
     where
@@ -161,6 +176,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
     /// # Safety
     ///
     /// The caller must ensure `val` is safe to copy.
+    #[track_caller]
     #[inline]
     pub unsafe fn copy_from_ref_in_unchecked(
         val: &T,
@@ -180,6 +196,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
     /// # Safety
     ///
     /// The caller must ensure `val` points to a valid `T`
+    #[track_caller]
     #[inline]
     pub unsafe fn copy_from_ptr_in(val: *const T, alloc: A) -> Result<HeapVal<T, A>, AllocError>
     where
@@ -200,6 +217,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
     /// # Safety
     ///
     /// The caller must ensure `val` points to a valid, safe-to-copy `T`.
+    #[track_caller]
     #[inline]
     pub unsafe fn copy_from_ptr_in_unchecked(
         val: *const T,
@@ -228,7 +246,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
     }
 
     /// Destructor to drop the value and deallocate the memory.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn drop_and_dealloc(self) {
         unsafe {
@@ -236,7 +254,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
         }
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn reset(&self) {
         self.ptr.as_ptr().drop_in_place();
@@ -244,7 +262,7 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
     }
 
     /// Destructor to drop the value, then zero and deallocate the memory.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub fn drop_zero_and_dealloc(self) {
         unsafe {
@@ -252,12 +270,12 @@ impl<T: ?Sized, A: Alloc> HeapVal<T, A> {
         }
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn reset_zero(&self) {
         self.ptr.as_ptr().drop_in_place();
         let layout = self.ptr.layout();
-        (self.ptr.as_ptr() as *mut u8).write_bytes(0, layout.size());
+        secure_zero(self.ptr.as_ptr() as *mut u8, layout.size());
         self.alloc.dealloc(self.ptr.cast(), layout);
     }
 
@@ -401,6 +419,10 @@ impl<T: ?Sized + Hash, A: Alloc> Hash for HeapVal<T, A> {
     }
 }
 
+// SAFETY: `HeapVal` owns its value and allocator, so it's as thread-safe as they are.
+unsafe impl<T: ?Sized + Send, A: Alloc + Send> Send for HeapVal<T, A> {}
+unsafe impl<T: ?Sized + Sync, A: Alloc + Sync> Sync for HeapVal<T, A> {}
+
 #[cfg(all(feature = "drop_for_owned", not(feature = "zero_drop_for_owned")))]
 impl<T: ?Sized, A: Alloc> Drop for HeapVal<T, A> {
     #[inline]
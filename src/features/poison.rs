@@ -0,0 +1,375 @@
+use crate::{
+    error::{AllocError, PanicOnViolation, Violation, ViolationHandler, ViolationKind},
+    spin::SpinLock,
+    Alloc, AllocExt, AllocPattern, DefaultAlloc, Owns,
+};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug, Formatter},
+    mem::ManuallyDrop,
+    ptr::{self, NonNull},
+};
+
+/// The default byte fresh blocks are filled with.
+pub const ALLOC_POISON: u8 = 0xA5;
+
+/// The default byte freed blocks are filled with.
+pub const FREE_POISON: u8 = 0xDD;
+
+/// A ring of freed blocks which haven't been given back to the inner allocator yet.
+struct Quarantine<const N: usize> {
+    blocks: [Option<(NonNull<u8>, Layout)>; N],
+    next: usize,
+}
+
+// SAFETY: the quarantine exclusively owns the blocks in it.
+unsafe impl<const N: usize> Send for Quarantine<N> {}
+
+impl<const N: usize> Quarantine<N> {
+    const fn new() -> Quarantine<N> {
+        Quarantine {
+            blocks: [None; N],
+            next: 0,
+        }
+    }
+
+    /// Adds a block, returning the oldest one if the quarantine was full.
+    fn push(&mut self, ptr: NonNull<u8>, layout: Layout) -> Option<(NonNull<u8>, Layout)> {
+        let evicted = self.blocks[self.next].replace((ptr, layout));
+        self.next = (self.next + 1) % N;
+        evicted
+    }
+}
+
+/// An allocator which poisons memory to make reads of uninitialized or freed memory stand out.
+///
+/// Fresh blocks from `alloc` are filled with [`ALLOC_POISON`] and freed blocks with
+/// [`FREE_POISON`], unless other patterns are set using [`with_patterns`](Poison::with_patterns).
+/// Blocks from `alloc_zeroed` are zeroed as usual. Resizing always moves the block, so stale
+/// pointers to the old block see poison too.
+///
+/// With a quarantine of `QUARANTINE` blocks, freed blocks are held back instead of being
+/// deallocated immediately. Once a block leaves the quarantine, its poison is checked, and writes
+/// to it after it was freed are reported to `H` as [`ViolationKind::UseAfterFree`].
+pub struct Poison<
+    A: Alloc = DefaultAlloc,
+    H: ViolationHandler = PanicOnViolation,
+    const QUARANTINE: usize = 0,
+> {
+    /// The inner allocator.
+    alloc: A,
+    /// The violation handler.
+    handler: H,
+    /// The byte fresh blocks are filled with.
+    alloc_pattern: u8,
+    /// The byte freed blocks are filled with.
+    free_pattern: u8,
+    /// The freed blocks which haven't been deallocated yet.
+    quarantine: SpinLock<Quarantine<QUARANTINE>>,
+}
+
+impl Poison {
+    /// Creates a new poisoning allocator backed by the default allocator, without a quarantine.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Poison {
+        Poison::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> Poison<A> {
+    /// Creates a new poisoning allocator backed by `alloc`, without a quarantine.
+    #[inline]
+    pub const fn new_in(alloc: A) -> Poison<A> {
+        Poison::with_handler_in(PanicOnViolation, alloc)
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler> Poison<A, H> {
+    /// Creates a new poisoning allocator backed by `alloc`, without a quarantine, which reports
+    /// violations to `handler`.
+    #[inline]
+    pub const fn with_handler_in(handler: H, alloc: A) -> Poison<A, H> {
+        Poison {
+            alloc,
+            handler,
+            alloc_pattern: ALLOC_POISON,
+            free_pattern: FREE_POISON,
+            quarantine: SpinLock::new(Quarantine::new()),
+        }
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler, const QUARANTINE: usize> Poison<A, H, QUARANTINE> {
+    /// Sets the bytes fresh and freed blocks are filled with.
+    #[must_use]
+    #[inline]
+    pub fn with_patterns(mut self, alloc_pattern: u8, free_pattern: u8) -> Self {
+        self.alloc_pattern = alloc_pattern;
+        self.free_pattern = free_pattern;
+        self
+    }
+
+    /// Changes the quarantine to hold up to `N` freed blocks, deallocating the ones currently in
+    /// it.
+    #[must_use]
+    pub fn with_quarantine<const N: usize>(self) -> Poison<A, H, N> {
+        self.flush();
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, and its quarantine is empty.
+        unsafe {
+            Poison {
+                alloc: ptr::read(&this.alloc),
+                handler: ptr::read(&this.handler),
+                alloc_pattern: this.alloc_pattern,
+                free_pattern: this.free_pattern,
+                quarantine: SpinLock::new(Quarantine::new()),
+            }
+        }
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Gets a reference to the violation handler.
+    #[inline]
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Returns the byte fresh blocks are filled with.
+    #[must_use]
+    #[inline]
+    pub const fn alloc_pattern(&self) -> u8 {
+        self.alloc_pattern
+    }
+
+    /// Returns the byte freed blocks are filled with.
+    #[must_use]
+    #[inline]
+    pub const fn free_pattern(&self) -> u8 {
+        self.free_pattern
+    }
+
+    /// Returns the number of freed blocks in the quarantine.
+    pub fn quarantined(&self) -> usize {
+        self.quarantine
+            .lock()
+            .blocks
+            .iter()
+            .filter(|b| b.is_some())
+            .count()
+    }
+
+    /// Checks and deallocates all blocks in the quarantine.
+    #[track_caller]
+    pub fn flush(&self) {
+        let mut quarantine = self.quarantine.lock();
+        for block in &mut quarantine.blocks {
+            if let Some((ptr, layout)) = block.take() {
+                unsafe {
+                    self.release(ptr, layout);
+                }
+            }
+        }
+    }
+
+    /// Checks that the freed block at `ptr` is still poisoned, then deallocates it.
+    #[track_caller]
+    unsafe fn release(&self, ptr: NonNull<u8>, layout: Layout) {
+        if (0..layout.size()).any(|i| *ptr.as_ptr().add(i) != self.free_pattern) {
+            self.handler.report(&Violation {
+                kind: ViolationKind::UseAfterFree,
+                ptr,
+                layout,
+            });
+        }
+        self.alloc.dealloc(ptr, layout);
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler, const QUARANTINE: usize> Alloc for Poison<A, H, QUARANTINE> {
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.alloc.alloc_filled(layout, self.alloc_pattern)
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.alloc.alloc_zeroed(layout)
+    }
+
+    /// Poisons the block at `ptr`, then deallocates it or puts it into the quarantine.
+    ///
+    /// If this pushes another block out of the quarantine, that block is checked and deallocated.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 {
+            self.alloc.dealloc(ptr, layout);
+            return;
+        }
+        ptr.as_ptr().write_bytes(self.free_pattern, layout.size());
+        if QUARANTINE == 0 {
+            self.alloc.dealloc(ptr, layout);
+            return;
+        }
+        let evicted = self.quarantine.lock().push(ptr, layout);
+        if let Some((ptr, layout)) = evicted {
+            self.release(ptr, layout);
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::grow(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::None,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::grow(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::Zero,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::shrink(self, ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::ralloc(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::None,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::ralloc(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::Zero,
+        )
+    }
+}
+
+impl<A: Owns, H: ViolationHandler, const QUARANTINE: usize> Owns for Poison<A, H, QUARANTINE> {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.alloc.owns(ptr, layout)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, H: ViolationHandler, const QUARANTINE: usize>
+    crate::in_place::ResizeInPlace for Poison<A, H, QUARANTINE>
+{
+    /// Grows the given block in place, filling the new bytes with the allocation poison.
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.alloc
+            .grow_in_place_filled(ptr, old_layout, new_size, self.alloc_pattern)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.alloc.shrink_in_place(ptr, old_layout, new_size)
+    }
+}
+
+impl<A: Alloc, H: ViolationHandler, const QUARANTINE: usize> Drop for Poison<A, H, QUARANTINE> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl<A: Alloc + Default, H: ViolationHandler + Default> Default for Poison<A, H> {
+    #[inline]
+    fn default() -> Poison<A, H> {
+        Poison::with_handler_in(H::default(), A::default())
+    }
+}
+
+impl<A: Alloc + Debug, H: ViolationHandler, const QUARANTINE: usize> Debug
+    for Poison<A, H, QUARANTINE>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Poison")
+            .field("alloc", &self.alloc)
+            .field("alloc_pattern", &self.alloc_pattern)
+            .field("free_pattern", &self.free_pattern)
+            .field("quarantined", &self.quarantined())
+            .finish_non_exhaustive()
+    }
+}
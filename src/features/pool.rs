@@ -0,0 +1,479 @@
+use crate::{
+    error::{AllocError, ArithOp},
+    helpers::zsl_check,
+    Alloc, DefaultAlloc, Owns,
+};
+use core::{
+    alloc::Layout,
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+    mem::{align_of, size_of},
+    ptr::NonNull,
+};
+
+/// The default number of slots in each slab of a [`Pool`].
+pub const DEFAULT_SLOTS_PER_SLAB: usize = 64;
+
+/// The header at the start of every slab.
+struct SlabHeader {
+    /// The next slab.
+    next: Option<NonNull<SlabHeader>>,
+    /// The number of slots in this slab which are in use.
+    used: usize,
+}
+
+/// A free slot, linked into the pool's free list.
+struct FreeSlot {
+    next: Option<NonNull<FreeSlot>>,
+}
+
+/// An allocator which hands out fixed-size slots from slabs obtained from `A`.
+///
+/// Any layout whose size and alignment fit in the slot is satisfied; other layouts fail with
+/// [`AllocError::AllocFailed`]. Allocation and deallocation are constant time. Slabs whose slots
+/// are all free can be returned to `A` using [`release_empty`](Pool::release_empty), and all slabs
+/// are returned when the pool is dropped.
+///
+/// Slabs are aligned to their own size, which is a power of two, so `A` must support large
+/// alignments.
+///
+/// This allocator uses interior mutability and isn't [`Sync`]. Share it by reference, e.g. by
+/// using `&Pool<A>` as the allocator of a [`HeapVal`](crate::owned::HeapVal).
+pub struct Pool<A: Alloc = DefaultAlloc> {
+    /// The backing allocator.
+    alloc: A,
+    /// The size and alignment of each slot.
+    slot: Layout,
+    /// The offset of the first slot in a slab.
+    first_slot: usize,
+    /// The number of slots in each slab.
+    slots_per_slab: usize,
+    /// The layout of each slab.
+    slab: Layout,
+    /// All slabs.
+    slabs: Cell<Option<NonNull<SlabHeader>>>,
+    /// The free slots of all slabs.
+    free: Cell<Option<NonNull<FreeSlot>>>,
+}
+
+impl Pool {
+    /// Creates a new, empty pool with slots fitting `slot`, backed by the default allocator.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::LayoutError`] if the computed slab layout is invalid.
+    #[inline]
+    pub fn new(slot: Layout) -> Result<Pool, AllocError> {
+        Pool::new_in(slot, DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> Pool<A> {
+    /// Creates a new, empty pool with slots fitting `slot`, backed by `alloc`.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::LayoutError`] if the computed slab layout is invalid.
+    #[inline]
+    pub fn new_in(slot: Layout, alloc: A) -> Result<Pool<A>, AllocError> {
+        Pool::with_slots_in(slot, DEFAULT_SLOTS_PER_SLAB, alloc)
+    }
+
+    /// Creates a new, empty pool with slots fitting values of type `T`, backed by `alloc`.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::LayoutError`] if the computed slab layout is invalid.
+    #[inline]
+    pub fn for_type_in<T>(alloc: A) -> Result<Pool<A>, AllocError> {
+        Pool::new_in(Layout::new::<T>(), alloc)
+    }
+
+    /// Creates a new, empty pool with slots fitting `slot` and at least `slots_per_slab` slots per
+    /// slab, backed by `alloc`.
+    ///
+    /// Slabs are rounded up to a power of two in size, and as many slots as fit are used. No
+    /// memory is allocated until the first allocation.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::LayoutError`] if the computed slab layout is invalid.
+    /// - [`AllocError::ArithmeticOverflow`] if the slab size overflows.
+    pub fn with_slots_in(
+        slot: Layout,
+        slots_per_slab: usize,
+        alloc: A,
+    ) -> Result<Pool<A>, AllocError> {
+        // slots must be able to hold a free list link.
+        let align = if slot.align() < align_of::<FreeSlot>() {
+            align_of::<FreeSlot>()
+        } else {
+            slot.align()
+        };
+        let size = if slot.size() < size_of::<FreeSlot>() {
+            size_of::<FreeSlot>()
+        } else {
+            slot.size()
+        };
+        let slot = Layout::from_size_align(size, align)
+            .map_err(|_| AllocError::LayoutError(size, align))?
+            .pad_to_align();
+        let slots_per_slab = if slots_per_slab == 0 {
+            1
+        } else {
+            slots_per_slab
+        };
+
+        let first_slot = (size_of::<SlabHeader>() + align - 1) & !(align - 1);
+        let slab_size = match slot
+            .size()
+            .checked_mul(slots_per_slab)
+            .and_then(|n| n.checked_add(first_slot))
+            .and_then(usize::checked_next_power_of_two)
+        {
+            Some(n) => n,
+            None => {
+                return Err(AllocError::ArithmeticOverflow(
+                    slot.size(),
+                    ArithOp::Mul,
+                    slots_per_slab,
+                ))
+            }
+        };
+        let slab = Layout::from_size_align(slab_size, slab_size)
+            .map_err(|_| AllocError::LayoutError(slab_size, slab_size))?;
+        // use the space the rounding added, too.
+        let slots_per_slab = (slab_size - first_slot) / slot.size();
+
+        Ok(Pool {
+            alloc,
+            slot,
+            first_slot,
+            slots_per_slab,
+            slab,
+            slabs: Cell::new(None),
+            free: Cell::new(None),
+        })
+    }
+
+    /// Gets a reference to the backing allocator.
+    #[inline]
+    pub const fn backing(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the layout of each slot.
+    ///
+    /// This may be bigger and more aligned than the requested layout.
+    #[inline]
+    pub const fn slot_layout(&self) -> Layout {
+        self.slot
+    }
+
+    /// Returns the number of slots in each slab.
+    #[inline]
+    pub const fn slots_per_slab(&self) -> usize {
+        self.slots_per_slab
+    }
+
+    /// Returns whether a block of `layout` fits in a slot.
+    #[inline]
+    pub const fn fits(&self, layout: Layout) -> bool {
+        layout.size() <= self.slot.size() && layout.align() <= self.slot.align()
+    }
+
+    /// Returns the number of slabs currently held.
+    #[must_use]
+    pub fn slabs(&self) -> usize {
+        let mut cnt = 0;
+        let mut slab = self.slabs.get();
+        while let Some(s) = slab {
+            cnt += 1;
+            slab = unsafe { (*s.as_ptr()).next };
+        }
+        cnt
+    }
+
+    /// Returns the number of slots currently in use.
+    #[must_use]
+    pub fn in_use(&self) -> usize {
+        let mut cnt = 0;
+        let mut slab = self.slabs.get();
+        while let Some(s) = slab {
+            unsafe {
+                cnt += (*s.as_ptr()).used;
+                slab = (*s.as_ptr()).next;
+            }
+        }
+        cnt
+    }
+
+    /// Returns every slab with no slots in use to the backing allocator, returning how many were
+    /// released.
+    pub fn release_empty(&self) -> usize {
+        let is_empty =
+            |slot: NonNull<FreeSlot>| unsafe { (*self.slab_of(slot.cast()).as_ptr()).used == 0 };
+
+        // unlink the slots of empty slabs from the free list first.
+        let mut prev: Option<NonNull<FreeSlot>> = None;
+        let mut cur = self.free.get();
+        while let Some(slot) = cur {
+            let next = unsafe { (*slot.as_ptr()).next };
+            if is_empty(slot) {
+                match prev {
+                    Some(p) => unsafe { (*p.as_ptr()).next = next },
+                    None => self.free.set(next),
+                }
+            } else {
+                prev = Some(slot);
+            }
+            cur = next;
+        }
+
+        let mut released = 0;
+        let mut prev: Option<NonNull<SlabHeader>> = None;
+        let mut cur = self.slabs.get();
+        while let Some(slab) = cur {
+            let (next, used) = unsafe { ((*slab.as_ptr()).next, (*slab.as_ptr()).used) };
+            if used == 0 {
+                match prev {
+                    Some(p) => unsafe { (*p.as_ptr()).next = next },
+                    None => self.slabs.set(next),
+                }
+                unsafe {
+                    self.alloc.dealloc(slab.cast(), self.slab);
+                }
+                released += 1;
+            } else {
+                prev = Some(slab);
+            }
+            cur = next;
+        }
+        released
+    }
+
+    /// Returns the slab containing `ptr`.
+    #[inline]
+    fn slab_of(&self, ptr: NonNull<u8>) -> NonNull<SlabHeader> {
+        let offset = ptr.as_ptr() as usize & (self.slab.size() - 1);
+        unsafe { NonNull::new_unchecked(ptr.as_ptr().sub(offset)).cast() }
+    }
+
+    /// Allocates a new slab and pushes its slots onto the free list.
+    fn push_slab(&self) -> Result<(), AllocError> {
+        let slab = self.alloc.alloc(self.slab)?;
+        unsafe {
+            slab.cast::<SlabHeader>().as_ptr().write(SlabHeader {
+                next: self.slabs.get(),
+                used: 0,
+            });
+            // push in reverse so slots are handed out in address order.
+            for i in (0..self.slots_per_slab).rev() {
+                let slot = NonNull::new_unchecked(
+                    slab.as_ptr().add(self.first_slot + i * self.slot.size()),
+                )
+                .cast::<FreeSlot>();
+                slot.as_ptr().write(FreeSlot {
+                    next: self.free.get(),
+                });
+                self.free.set(Some(slot));
+            }
+        }
+        self.slabs.set(Some(slab.cast()));
+        Ok(())
+    }
+
+    /// Returns whether the slot at `ptr` can hold a block described by `new_layout`, so resizing
+    /// the block in it to `new_layout` doesn't need to move it.
+    #[inline]
+    fn resizes_in_place(&self, ptr: NonNull<u8>, new_layout: Layout) -> bool {
+        new_layout.size() != 0
+            && self.fits(new_layout)
+            && ptr.as_ptr() as usize % new_layout.align() == 0
+    }
+}
+
+impl<A: Alloc> Alloc for Pool<A> {
+    /// Attempts to allocate a slot fitting the given [`Layout`].
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if `layout` doesn't fit in a slot.
+    /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
+    /// - Any error returned by the backing allocator when allocating a new slab.
+    #[track_caller]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        zsl_check(layout, |layout| {
+            if !self.fits(layout) {
+                return Err(AllocError::AllocFailed(layout));
+            }
+            if self.free.get().is_none() {
+                self.push_slab()?;
+            }
+            let slot = match self.free.get() {
+                Some(slot) => slot,
+                None => return Err(AllocError::AllocFailed(layout)),
+            };
+            unsafe {
+                self.free.set((*slot.as_ptr()).next);
+                (*self.slab_of(slot.cast()).as_ptr()).used += 1;
+            }
+            Ok(slot.cast())
+        })
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, _: Layout) {
+        (*self.slab_of(ptr).as_ptr()).used -= 1;
+        let slot = ptr.cast::<FreeSlot>();
+        slot.as_ptr().write(FreeSlot {
+            next: self.free.get(),
+        });
+        self.free.set(Some(slot));
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size() && self.resizes_in_place(ptr, new_layout) {
+            Ok(ptr)
+        } else {
+            crate::grow(
+                self,
+                ptr,
+                old_layout,
+                new_layout,
+                crate::AllocPattern::<fn(usize) -> u8>::None,
+            )
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size() && self.resizes_in_place(ptr, new_layout) {
+            ptr.as_ptr()
+                .add(old_layout.size())
+                .write_bytes(0, new_layout.size() - old_layout.size());
+            Ok(ptr)
+        } else {
+            crate::grow(
+                self,
+                ptr,
+                old_layout,
+                new_layout,
+                crate::AllocPattern::<fn(usize) -> u8>::Zero,
+            )
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() <= old_layout.size() && self.resizes_in_place(ptr, new_layout) {
+            Ok(ptr)
+        } else {
+            crate::shrink(self, ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size() {
+            self.grow(ptr, old_layout, new_layout)
+        } else {
+            self.shrink(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() >= old_layout.size() {
+            self.grow_zeroed(ptr, old_layout, new_layout)
+        } else {
+            self.shrink(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, _: NonNull<u8>, _: Layout) -> usize {
+        self.slot.size()
+    }
+}
+
+impl<A: Alloc> Owns for Pool<A> {
+    fn owns(&self, ptr: NonNull<u8>, _: Layout) -> bool {
+        // `ptr` may belong to anything, so stick to addresses rather than `slab_of`.
+        let addr = ptr.as_ptr() as usize;
+        let slab = addr & !(self.slab.size() - 1);
+        let slots = slab + self.first_slot;
+        if addr < slots || addr >= slots + self.slots_per_slab * self.slot.size() {
+            return false;
+        }
+        let mut cur = self.slabs.get();
+        while let Some(s) = cur {
+            if s.as_ptr() as usize == slab {
+                return true;
+            }
+            cur = unsafe { (*s.as_ptr()).next };
+        }
+        false
+    }
+}
+
+impl<A: Alloc + Debug> Debug for Pool<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("alloc", &self.alloc)
+            .field("slot", &self.slot)
+            .field("slots_per_slab", &self.slots_per_slab)
+            .field("slabs", &self.slabs())
+            .field("in_use", &self.in_use())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Alloc> Drop for Pool<A> {
+    fn drop(&mut self) {
+        let mut slab = self.slabs.get();
+        while let Some(s) = slab {
+            unsafe {
+                slab = (*s.as_ptr()).next;
+                self.alloc.dealloc(s.cast(), self.slab);
+            }
+        }
+    }
+}
+
+// SAFETY: the slabs are owned by the pool, so moving it to another thread is as safe as moving
+//  `A`. it isn't `Sync` because of the `Cell`s.
+unsafe impl<A: Alloc + Send> Send for Pool<A> {}
@@ -0,0 +1,282 @@
+use crate::{error::AllocError, Alloc, Owns};
+use core::{alloc::Layout, ptr::NonNull};
+
+/// An allocator which sends layouts of at most `THRESHOLD` bytes to `Small` and bigger ones to
+/// `Large`.
+///
+/// Routing only depends on the layout, so neither allocator needs to implement [`Owns`]. Resizing
+/// a block across the threshold moves it to the other allocator, preserving its contents.
+///
+/// Wrap either side in a [`Stats`](crate::stats::Stats) to observe its traffic separately.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Segregate<const THRESHOLD: usize, Small: Alloc, Large: Alloc> {
+    /// The allocator for layouts of at most `THRESHOLD` bytes.
+    pub small: Small,
+    /// The allocator for layouts of more than `THRESHOLD` bytes.
+    pub large: Large,
+}
+
+impl<const THRESHOLD: usize, Small: Alloc, Large: Alloc> Segregate<THRESHOLD, Small, Large> {
+    /// Creates a new segregating allocator from an allocator for small and one for large layouts.
+    #[inline]
+    pub const fn new(small: Small, large: Large) -> Segregate<THRESHOLD, Small, Large> {
+        Segregate { small, large }
+    }
+
+    /// Splits this allocator into its small and large allocators.
+    #[inline]
+    pub fn into_parts(self) -> (Small, Large) {
+        (self.small, self.large)
+    }
+
+    /// Returns whether `layout` is sent to the small allocator.
+    #[inline]
+    #[must_use]
+    pub const fn is_small(layout: Layout) -> bool {
+        layout.size() <= THRESHOLD
+    }
+
+    /// Moves a block from `from` to a new block from `to`, copying as many bytes as fit.
+    #[track_caller]
+    unsafe fn move_between<F: Alloc, T: Alloc>(
+        from: &F,
+        to: &T,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zero: bool,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = if zero {
+            to.alloc_zeroed(new_layout)?
+        } else {
+            to.alloc(new_layout)?
+        };
+        let cnt = if old_layout.size() < new_layout.size() {
+            old_layout.size()
+        } else {
+            new_layout.size()
+        };
+        ptr.as_ptr().copy_to_nonoverlapping(new_ptr.as_ptr(), cnt);
+        from.dealloc(ptr, old_layout);
+        Ok(new_ptr)
+    }
+
+    /// Moves a block to the other side, as its new layout crossed the threshold.
+    #[track_caller]
+    #[inline]
+    unsafe fn cross(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zero: bool,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if Self::is_small(old_layout) {
+            Self::move_between(&self.small, &self.large, ptr, old_layout, new_layout, zero)
+        } else {
+            Self::move_between(&self.large, &self.small, ptr, old_layout, new_layout, zero)
+        }
+    }
+}
+
+impl<const THRESHOLD: usize, Small: Alloc, Large: Alloc> Alloc
+    for Segregate<THRESHOLD, Small, Large>
+{
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        if Self::is_small(layout) {
+            self.small.alloc(layout)
+        } else {
+            self.large.alloc(layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        if Self::is_small(layout) {
+            self.small.alloc_zeroed(layout)
+        } else {
+            self.large.alloc_zeroed(layout)
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        if Self::is_small(layout) {
+            self.small.dealloc(ptr, layout);
+        } else {
+            self.large.dealloc(ptr, layout);
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        match (Self::is_small(old_layout), Self::is_small(new_layout)) {
+            (true, true) => self.small.grow(ptr, old_layout, new_layout),
+            (false, false) => self.large.grow(ptr, old_layout, new_layout),
+            (true, false) => self.cross(ptr, old_layout, new_layout, false),
+            (false, true) => Err(AllocError::GrowSmallerNewLayout(
+                old_layout.size(),
+                new_layout.size(),
+            )),
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        match (Self::is_small(old_layout), Self::is_small(new_layout)) {
+            (true, true) => self.small.grow_zeroed(ptr, old_layout, new_layout),
+            (false, false) => self.large.grow_zeroed(ptr, old_layout, new_layout),
+            (true, false) => self.cross(ptr, old_layout, new_layout, true),
+            (false, true) => Err(AllocError::GrowSmallerNewLayout(
+                old_layout.size(),
+                new_layout.size(),
+            )),
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        match (Self::is_small(old_layout), Self::is_small(new_layout)) {
+            (true, true) => self.small.shrink(ptr, old_layout, new_layout),
+            (false, false) => self.large.shrink(ptr, old_layout, new_layout),
+            (false, true) => self.cross(ptr, old_layout, new_layout, false),
+            (true, false) => Err(AllocError::ShrinkBiggerNewLayout(
+                old_layout.size(),
+                new_layout.size(),
+            )),
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        match (Self::is_small(old_layout), Self::is_small(new_layout)) {
+            (true, true) => self.small.realloc(ptr, old_layout, new_layout),
+            (false, false) => self.large.realloc(ptr, old_layout, new_layout),
+            _ => self.cross(ptr, old_layout, new_layout, false),
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        match (Self::is_small(old_layout), Self::is_small(new_layout)) {
+            (true, true) => self.small.realloc_zeroed(ptr, old_layout, new_layout),
+            (false, false) => self.large.realloc_zeroed(ptr, old_layout, new_layout),
+            _ => self.cross(ptr, old_layout, new_layout, true),
+        }
+    }
+
+    /// Returns the usable size of the block at `ptr`.
+    ///
+    /// For blocks from the small allocator, this is capped at `THRESHOLD`, so describing the block
+    /// using the usable size still routes it to the small allocator.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        if Self::is_small(layout) {
+            let usable = self.small.usable_size(ptr, layout);
+            if usable > THRESHOLD {
+                THRESHOLD
+            } else {
+                usable
+            }
+        } else {
+            self.large.usable_size(ptr, layout)
+        }
+    }
+}
+
+impl<const THRESHOLD: usize, Small: Owns, Large: Owns> Owns for Segregate<THRESHOLD, Small, Large> {
+    const PRECISE: bool = Small::PRECISE && Large::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        if Self::is_small(layout) {
+            self.small.owns(ptr, layout)
+        } else {
+            self.large.owns(ptr, layout)
+        }
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<const THRESHOLD: usize, Small, Large> crate::in_place::ResizeInPlace
+    for Segregate<THRESHOLD, Small, Large>
+where
+    Small: crate::in_place::ResizeInPlace,
+    Large: crate::in_place::ResizeInPlace,
+{
+    /// Grows the given block in place, which fails if the new size crosses the threshold.
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if !Self::is_small(old_layout) {
+            self.large.grow_in_place(ptr, old_layout, new_size)
+        } else if new_size <= THRESHOLD {
+            self.small.grow_in_place(ptr, old_layout, new_size)
+        } else {
+            Err(AllocError::Other(crate::in_place::CANNOT_RESIZE_IP))
+        }
+    }
+
+    /// Shrinks the given block in place, which fails if the new size crosses the threshold.
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if Self::is_small(old_layout) {
+            self.small.shrink_in_place(ptr, old_layout, new_size)
+        } else if new_size > THRESHOLD || new_size == 0 {
+            self.large.shrink_in_place(ptr, old_layout, new_size)
+        } else {
+            Err(AllocError::Other(crate::in_place::CANNOT_RESIZE_IP))
+        }
+    }
+}
@@ -4,13 +4,18 @@ use crate::{
     stats::AllocRes::{Fail, Succ},
     Alloc, AllocError, DefaultAlloc,
 };
-use alloc::{alloc::Layout, format, string::ToString};
+use alloc::{
+    alloc::{GlobalAlloc, Layout},
+    format,
+    string::ToString,
+};
 use core::{
     fmt::{self, Display, Formatter},
+    panic::Location,
     ptr::{null_mut, NonNull},
     sync::atomic::{
         AtomicUsize,
-        Ordering::{Acquire, Release},
+        Ordering::{AcqRel, Acquire, SeqCst},
     },
 };
 
@@ -50,16 +55,12 @@ macro_rules! atomic_total_ops {
     ($this:expr $(, $field:ident)?) => {
             #[inline]
             fn inc_total_bytes_allocated(&self, bytes: usize) -> usize {
-                let res = self$(.$field)?.load(Acquire) + bytes;
-                self$(.$field)?.store(res, Release);
-                res
+                self$(.$field)?.fetch_add(bytes, AcqRel).wrapping_add(bytes)
             }
 
             #[inline]
             fn dec_total_bytes_allocated(&self, bytes: usize) -> usize {
-                let res = self$(.$field)?.load(Acquire) - bytes;
-                self$(.$field)?.store(res, Release);
-                res
+                saturating_fetch_sub(&self$(.$field)?, bytes)
             }
 
             #[inline]
@@ -69,6 +70,15 @@ macro_rules! atomic_total_ops {
     };
 }
 
+/// Atomically subtracts `bytes` from `n` without going below zero, and returns the new value.
+#[inline]
+fn saturating_fetch_sub(n: &AtomicUsize, bytes: usize) -> usize {
+    let prev = match n.fetch_update(AcqRel, Acquire, |cur| Some(cur.saturating_sub(bytes))) {
+        Ok(prev) | Err(prev) => prev,
+    };
+    prev.saturating_sub(bytes)
+}
+
 // byte counter-only logger (no stat)
 impl StatsLogger for AtomicUsize {
     fn log(&self, _: AllocRes) {}
@@ -322,6 +332,483 @@ pub type StringLog = FmtLog<String>;
 /// A logger that writes to a string slice.
 pub type StrLog<'s> = FmtLog<&'s str>;
 
+#[cfg(feature = "std")]
+/// The statistics of a single call site, as gathered by [`CallSiteLog`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CallSite {
+    /// The file of the call site.
+    pub file: &'static str,
+    /// The line of the call site.
+    pub line: u32,
+    /// The number of successful operations.
+    pub ops: usize,
+    /// The number of failed operations.
+    pub failures: usize,
+    /// The number of bytes allocated, including the bytes added by growing blocks.
+    pub allocated: usize,
+    /// The number of bytes freed, including the bytes removed by shrinking blocks.
+    pub freed: usize,
+}
+
+#[cfg(feature = "std")]
+/// A logger which aggregates operations by the `file:line` they were called from.
+///
+/// Use [`sites`](CallSiteLog::sites) to find which call sites allocate the most memory.
+#[derive(Debug, Default)]
+pub struct CallSiteLog {
+    /// The statistics of every call site, keyed by file and line.
+    pub sites: std::sync::Mutex<alloc::collections::BTreeMap<(&'static str, u32), CallSite>>,
+    /// The total number of bytes allocated.
+    pub total: AtomicUsize,
+}
+
+#[cfg(feature = "std")]
+impl CallSiteLog {
+    /// Creates a new, empty [`CallSiteLog`].
+    #[must_use]
+    #[inline]
+    pub fn new() -> CallSiteLog {
+        CallSiteLog::default()
+    }
+
+    /// Returns the statistics of every call site, with the most bytes allocated first.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the inner [`Mutex`](std::sync::Mutex) is poisoned.
+    pub fn sites(&self) -> Vec<CallSite> {
+        let mut sites: Vec<CallSite> = self
+            .sites
+            .lock()
+            .expect("inner `Mutex` for `CallSiteLog` was poisoned")
+            .values()
+            .copied()
+            .collect();
+        sites.sort_by_key(|site| core::cmp::Reverse(site.allocated));
+        sites
+    }
+}
+
+#[cfg(feature = "std")]
+impl StatsLogger for CallSiteLog {
+    fn log(&self, stat: AllocRes) {
+        let (succ, stat) = match &stat {
+            Succ(stat) => (true, stat),
+            Fail(stat) => (false, stat),
+        };
+        let location = stat.location();
+        let mut sites = self
+            .sites
+            .lock()
+            .expect("inner `Mutex` for `CallSiteLog` was poisoned");
+        let site = sites
+            .entry((location.file(), location.line()))
+            .or_insert(CallSite {
+                file: location.file(),
+                line: location.line(),
+                ..CallSite::default()
+            });
+        if !succ {
+            site.failures += 1;
+            return;
+        }
+        site.ops += 1;
+        match stat {
+            AllocStat::Alloc { region, .. } => site.allocated += region.size,
+            AllocStat::Realloc { info, .. } | AllocStat::InPlace { info, .. } => {
+                site.allocated += info.new.size.saturating_sub(info.old.size);
+                site.freed += info.old.size.saturating_sub(info.new.size);
+            }
+            AllocStat::Free { region, .. } => site.freed += region.size,
+        }
+    }
+
+    atomic_total_ops!(self, total);
+}
+
+#[cfg(feature = "std")]
+impl Display for CallSiteLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "allocations by call site:")?;
+        for site in self.sites() {
+            write!(
+                f,
+                "\n  {}:{}: {} bytes allocated, {} bytes freed in {} operations ({} failed)",
+                site.file, site.line, site.allocated, site.freed, site.ops, site.failures
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A logger which counts bytes and operations using atomics only.
+///
+/// Unlike the byte counter of the other loggers, this also tracks the peak number of bytes
+/// allocated and how many operations of each kind happened. Use [`snapshot`](Counters::snapshot)
+/// to read all of them at once.
+///
+/// Snapshots are made of logged operations only: [`total`](StatsLogger::total) follows
+/// `inc_total_bytes_allocated` and `dec_total_bytes_allocated` right away, while the total in a
+/// snapshot changes once the operation is logged, together with the other counters.
+#[derive(Debug, Default)]
+pub struct Counters {
+    /// The number of bytes allocated currently.
+    total: AtomicUsize,
+    /// The number of bytes allocated currently, according to the logged operations.
+    logged: AtomicUsize,
+    /// The highest number of bytes allocated at once, according to the logged operations.
+    peak: AtomicUsize,
+    /// The number of successful allocations.
+    allocs: AtomicUsize,
+    /// The number of deallocations.
+    frees: AtomicUsize,
+    /// The number of successful grows and reallocations to a bigger or equal size.
+    grows: AtomicUsize,
+    /// The number of successful shrinks and reallocations to a smaller size.
+    shrinks: AtomicUsize,
+    /// The number of failed operations.
+    failures: AtomicUsize,
+    /// The number of updates in progress.
+    writers: AtomicUsize,
+    /// The number of finished updates.
+    version: AtomicUsize,
+}
+
+/// The counters of a [`Counters`] logger at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StatsSnapshot {
+    /// The number of bytes allocated.
+    pub total: usize,
+    /// The highest number of bytes allocated at once.
+    pub peak: usize,
+    /// The number of successful allocations.
+    pub allocs: usize,
+    /// The number of deallocations.
+    pub frees: usize,
+    /// The number of successful grows and reallocations to a bigger or equal size.
+    pub grows: usize,
+    /// The number of successful shrinks and reallocations to a smaller size.
+    pub shrinks: usize,
+    /// The number of failed operations.
+    pub failures: usize,
+}
+
+impl Counters {
+    /// Creates a new [`Counters`] logger with all counters at zero.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Counters {
+        Counters {
+            total: AtomicUsize::new(0),
+            logged: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            allocs: AtomicUsize::new(0),
+            frees: AtomicUsize::new(0),
+            grows: AtomicUsize::new(0),
+            shrinks: AtomicUsize::new(0),
+            failures: AtomicUsize::new(0),
+            writers: AtomicUsize::new(0),
+            version: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reads all counters at once.
+    ///
+    /// The snapshot never includes part of an operation, such as its bytes but not its kind. While
+    /// operations keep being recorded, this backs off between attempts.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let mut backoff = 1_u32;
+        loop {
+            let version = self.version.load(SeqCst);
+            if self.writers.load(SeqCst) == 0 {
+                let snapshot = StatsSnapshot {
+                    total: self.logged.load(SeqCst),
+                    peak: self.peak.load(SeqCst),
+                    allocs: self.allocs.load(SeqCst),
+                    frees: self.frees.load(SeqCst),
+                    grows: self.grows.load(SeqCst),
+                    shrinks: self.shrinks.load(SeqCst),
+                    failures: self.failures.load(SeqCst),
+                };
+                if self.writers.load(SeqCst) == 0 && self.version.load(SeqCst) == version {
+                    return snapshot;
+                }
+            }
+            if backoff < MAX_BACKOFF {
+                for _ in 0..backoff {
+                    core::hint::spin_loop();
+                }
+                backoff *= 2;
+            } else {
+                #[cfg(feature = "std")]
+                std::thread::yield_now();
+                #[cfg(not(feature = "std"))]
+                for _ in 0..backoff {
+                    core::hint::spin_loop();
+                }
+            }
+        }
+    }
+
+    /// Adds `bytes` to the logged total and updates the peak.
+    #[inline]
+    fn log_inc(&self, bytes: usize) {
+        let logged = self.logged.fetch_add(bytes, SeqCst).wrapping_add(bytes);
+        self.peak.fetch_max(logged, SeqCst);
+    }
+}
+
+/// The number of spins after which [`Counters::snapshot`] stops backing off further.
+const MAX_BACKOFF: u32 = 64;
+
+impl StatsLogger for Counters {
+    /// Counts the operation and applies its change in size to the logged total, as one update
+    /// which snapshots never observe halfway.
+    #[inline]
+    fn log(&self, stat: AllocRes) {
+        self.writers.fetch_add(1, SeqCst);
+        let counter = match stat {
+            Succ(AllocStat::Alloc { region, .. }) => {
+                self.log_inc(region.size);
+                &self.allocs
+            }
+            Succ(AllocStat::Realloc { info, .. } | AllocStat::InPlace { info, .. }) => {
+                if info.new.size >= info.old.size {
+                    self.log_inc(info.new.size - info.old.size);
+                    &self.grows
+                } else {
+                    saturating_fetch_sub(&self.logged, info.old.size - info.new.size);
+                    &self.shrinks
+                }
+            }
+            Succ(AllocStat::Free { region, .. }) => {
+                saturating_fetch_sub(&self.logged, region.size);
+                &self.frees
+            }
+            Fail(_) => &self.failures,
+        };
+        counter.fetch_add(1, SeqCst);
+        self.version.fetch_add(1, SeqCst);
+        self.writers.fetch_sub(1, SeqCst);
+    }
+
+    #[inline]
+    fn inc_total_bytes_allocated(&self, bytes: usize) -> usize {
+        self.total.fetch_add(bytes, SeqCst).wrapping_add(bytes)
+    }
+
+    #[inline]
+    fn dec_total_bytes_allocated(&self, bytes: usize) -> usize {
+        saturating_fetch_sub(&self.total, bytes)
+    }
+
+    #[inline]
+    fn total(&self) -> usize {
+        self.total.load(Acquire)
+    }
+}
+
+/// The number of buckets in a [`Histogram`].
+pub const HISTOGRAM_BUCKETS: usize = usize::BITS as usize + 1;
+
+/// How a [`Histogram`] groups allocation sizes into buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BucketKind {
+    /// The first bucket holds sizes up to 1, and each following bucket holds sizes up to twice
+    /// those of the previous one.
+    PowerOfTwo,
+    /// Each bucket holds this many sizes, starting at 0. The last bucket holds all bigger sizes.
+    Linear(usize),
+}
+
+/// A bucket of a [`Histogram`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bucket {
+    /// The smallest size in this bucket.
+    pub min: usize,
+    /// The largest size in this bucket.
+    pub max: usize,
+    /// The number of live blocks with a size in this bucket.
+    pub live: usize,
+    /// The number of blocks with a size in this bucket which were ever allocated.
+    pub cumulative: usize,
+}
+
+/// A logger which records the distribution of allocation sizes, using atomics only.
+///
+/// Blocks are counted both while they are live and cumulatively. A resized block counts as a new
+/// block of its new size, and stops counting as live in its old size.
+#[derive(Debug)]
+pub struct Histogram {
+    /// How sizes are grouped into buckets.
+    kind: BucketKind,
+    /// The number of live blocks in each bucket.
+    live: [AtomicUsize; HISTOGRAM_BUCKETS],
+    /// The number of blocks ever allocated in each bucket.
+    cumulative: [AtomicUsize; HISTOGRAM_BUCKETS],
+    /// The total number of bytes allocated.
+    total: AtomicUsize,
+}
+
+// only used to initialize arrays of atomics
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicUsize = AtomicUsize::new(0);
+
+impl Histogram {
+    /// Creates a new [`Histogram`] with power-of-two buckets.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Histogram {
+        Histogram::with_buckets(BucketKind::PowerOfTwo)
+    }
+
+    /// Creates a new [`Histogram`] with linear buckets of `width` sizes each.
+    ///
+    /// A `width` of zero is treated as one.
+    #[must_use]
+    #[inline]
+    pub const fn linear(width: usize) -> Histogram {
+        Histogram::with_buckets(BucketKind::Linear(width))
+    }
+
+    /// Creates a new [`Histogram`] with the given kind of buckets.
+    ///
+    /// A [`Linear`](BucketKind::Linear) width of zero is treated as one.
+    #[must_use]
+    #[inline]
+    pub const fn with_buckets(kind: BucketKind) -> Histogram {
+        Histogram {
+            kind: match kind {
+                BucketKind::Linear(0) => BucketKind::Linear(1),
+                kind => kind,
+            },
+            live: [ZERO; HISTOGRAM_BUCKETS],
+            cumulative: [ZERO; HISTOGRAM_BUCKETS],
+            total: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns how sizes are grouped into buckets.
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> BucketKind {
+        self.kind
+    }
+
+    /// Returns the index of the bucket holding `size`.
+    #[must_use]
+    pub const fn bucket_of(&self, size: usize) -> usize {
+        match self.kind {
+            BucketKind::PowerOfTwo => {
+                if size <= 1 {
+                    0
+                } else {
+                    (usize::BITS - (size - 1).leading_zeros()) as usize
+                }
+            }
+            BucketKind::Linear(width) => {
+                let i = size / width;
+                if i < HISTOGRAM_BUCKETS - 1 {
+                    i
+                } else {
+                    HISTOGRAM_BUCKETS - 1
+                }
+            }
+        }
+    }
+
+    /// Returns the bucket at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `idx` is not less than [`HISTOGRAM_BUCKETS`].
+    #[must_use]
+    pub fn bucket(&self, idx: usize) -> Bucket {
+        let (min, max) = match self.kind {
+            BucketKind::PowerOfTwo => match idx {
+                0 => (0, 1),
+                _ => (
+                    (1_usize << (idx - 1)) + 1,
+                    if idx < usize::BITS as usize {
+                        1 << idx
+                    } else {
+                        usize::MAX
+                    },
+                ),
+            },
+            BucketKind::Linear(width) => (
+                idx.saturating_mul(width),
+                if idx == HISTOGRAM_BUCKETS - 1 {
+                    usize::MAX
+                } else {
+                    (idx + 1).saturating_mul(width) - 1
+                },
+            ),
+        };
+        Bucket {
+            min,
+            max,
+            live: self.live[idx].load(Acquire),
+            cumulative: self.cumulative[idx].load(Acquire),
+        }
+    }
+
+    /// Returns an iterator over all buckets, from the smallest sizes to the largest.
+    pub fn buckets(&self) -> impl Iterator<Item = Bucket> + '_ {
+        (0..HISTOGRAM_BUCKETS).map(move |idx| self.bucket(idx))
+    }
+
+    #[inline]
+    fn add(&self, size: usize) {
+        let idx = self.bucket_of(size);
+        self.live[idx].fetch_add(1, AcqRel);
+        self.cumulative[idx].fetch_add(1, AcqRel);
+    }
+
+    #[inline]
+    fn remove(&self, size: usize) {
+        saturating_fetch_sub(&self.live[self.bucket_of(size)], 1);
+    }
+}
+
+impl Default for Histogram {
+    #[inline]
+    fn default() -> Histogram {
+        Histogram::new()
+    }
+}
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "size histogram (live / cumulative blocks):")?;
+        for bucket in self.buckets().filter(|b| b.cumulative != 0 || b.live != 0) {
+            write!(
+                f,
+                "\n  {}..={} bytes: {} / {}",
+                bucket.min, bucket.max, bucket.live, bucket.cumulative
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl StatsLogger for Histogram {
+    #[inline]
+    fn log(&self, stat: AllocRes) {
+        match stat {
+            Succ(AllocStat::Alloc { region, .. }) => self.add(region.size),
+            Succ(AllocStat::Realloc { info, .. } | AllocStat::InPlace { info, .. }) => {
+                self.remove(info.old.size);
+                self.add(info.new.size);
+            }
+            Succ(AllocStat::Free { region, .. }) => self.remove(region.size),
+            Fail(_) => {}
+        }
+    }
+
+    atomic_total_ops!(self, total);
+}
+
 /// Trait for logging statistics.
 ///
 /// This requires that `Self` allows safe mutable access via an immutable reference, such as the
@@ -368,12 +855,15 @@ pub enum AllocRes {
 
 impl Display for AllocRes {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (Succ(stat) | Fail(stat)) = self;
+        write!(f, "{}: ", stat.location())?;
         match self {
             Succ(stat) => match stat {
                 AllocStat::Alloc {
                     region,
                     kind,
                     total,
+                    ..
                 } => {
                     write!(
                         f,
@@ -391,7 +881,9 @@ impl Display for AllocRes {
                         }
                     )
                 }
-                AllocStat::Realloc { info, kind, total } => {
+                AllocStat::Realloc {
+                    info, kind, total, ..
+                } => {
                     write!(
                         f,
                         "Successful reallocation from {}->{} bytes with alignment {}->{}. \
@@ -402,19 +894,24 @@ impl Display for AllocRes {
                         info.new.align,
                         info.old.ptr,
                         info.new.ptr,
-                        match kind {
-                            AllocKind::Uninitialized =>
-                                "newly allocated bytes were uninitialized".to_string(),
-                            AllocKind::Zeroed => "newly allocated bytes were zeroed".to_string(),
-                            AllocKind::Filled(n) =>
-                                format!("newly allocated bytes were filled with the byte {n}"),
-                            AllocKind::Patterned =>
-                                "newly allocated bytes were filled with a pattern".to_string(),
-                            AllocKind::Shrink => "there were no newly allocated bytes".to_string(),
-                        }
+                        kind.new_bytes()
+                    )
+                }
+                AllocStat::InPlace {
+                    info, kind, total, ..
+                } => {
+                    write!(
+                        f,
+                        "Successful in-place resize from {}->{} bytes with alignment {} at {:p}, \
+                        and {}. ({total} total bytes allocated)",
+                        info.old.size,
+                        info.new.size,
+                        info.old.align,
+                        info.old.ptr,
+                        kind.new_bytes()
                     )
                 }
-                AllocStat::Free { region, total } => {
+                AllocStat::Free { region, total, .. } => {
                     write!(
                         f,
                         "Deallocation of {} bytes with alignment {} at {:p}. ({total} total bytes \
@@ -440,6 +937,13 @@ impl Display for AllocRes {
                         info.old.size, info.new.size, info.old.align, info.new.align, info.old.ptr
                     )
                 }
+                AllocStat::InPlace { info, .. } => {
+                    write!(
+                        f,
+                        "Failed in-place resize from {}->{} bytes with alignment {} at {:p}.",
+                        info.old.size, info.new.size, info.old.align, info.old.ptr
+                    )
+                }
                 // free is "infallible"
                 AllocStat::Free { .. } => unsafe { core::hint::unreachable_unchecked() },
             },
@@ -458,6 +962,8 @@ pub enum AllocStat {
         kind: AllocKind,
         /// The total number of bytes allocated currently.
         total: usize,
+        /// The call site of the operation.
+        location: &'static Location<'static>,
     },
     /// A reallocation (resizing) operation.
     Realloc {
@@ -467,6 +973,19 @@ pub enum AllocStat {
         kind: AllocKind,
         /// The total number of bytes allocated currently.
         total: usize,
+        /// The call site of the operation.
+        location: &'static Location<'static>,
+    },
+    /// A resize which kept the block where it was.
+    InPlace {
+        /// The old and new memory regions' info. Both regions start at the same address.
+        info: ResizeInfo,
+        /// The kind of allocation.
+        kind: AllocKind,
+        /// The total number of bytes allocated currently.
+        total: usize,
+        /// The call site of the operation.
+        location: &'static Location<'static>,
     },
     /// A deallocation operation.
     Free {
@@ -474,10 +993,24 @@ pub enum AllocStat {
         region: MemoryRegion,
         /// The total number of bytes allocated currently.
         total: usize,
+        /// The call site of the operation.
+        location: &'static Location<'static>,
     },
 }
 
 impl AllocStat {
+    /// Returns the call site of the operation.
+    #[must_use]
+    pub const fn location(&self) -> &'static Location<'static> {
+        match self {
+            AllocStat::Alloc { location, .. }
+            | AllocStat::Realloc { location, .. }
+            | AllocStat::InPlace { location, .. }
+            | AllocStat::Free { location, .. } => location,
+        }
+    }
+
+    #[track_caller]
     fn new_realloc(
         old_ptr: NonNull<u8>,
         new_ptr: *mut u8,
@@ -501,6 +1034,35 @@ impl AllocStat {
             },
             kind,
             total,
+            location: Location::caller(),
+        }
+    }
+
+    #[cfg(feature = "resize_in_place")]
+    #[track_caller]
+    fn new_in_place(
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        kind: AllocKind,
+        total: usize,
+    ) -> AllocStat {
+        AllocStat::InPlace {
+            info: ResizeInfo {
+                old: MemoryRegion {
+                    ptr: ptr.as_ptr(),
+                    size: old_layout.size(),
+                    align: old_layout.align(),
+                },
+                new: MemoryRegion {
+                    ptr: ptr.as_ptr(),
+                    size: new_size,
+                    align: old_layout.align(),
+                },
+            },
+            kind,
+            total,
+            location: Location::caller(),
         }
     }
 }
@@ -543,6 +1105,39 @@ pub enum AllocKind {
     Shrink,
 }
 
+impl AllocKind {
+    /// Describes the new bytes of a resize.
+    fn new_bytes(&self) -> alloc::string::String {
+        match self {
+            AllocKind::Uninitialized => "newly allocated bytes were uninitialized".to_string(),
+            AllocKind::Zeroed => "newly allocated bytes were zeroed".to_string(),
+            AllocKind::Filled(n) => format!("newly allocated bytes were filled with the byte {n}"),
+            AllocKind::Patterned => "newly allocated bytes were filled with a pattern".to_string(),
+            AllocKind::Shrink => "there were no newly allocated bytes".to_string(),
+        }
+    }
+
+    /// Returns `Shrink` instead of `self` if the block gets smaller.
+    #[inline]
+    fn or_shrink(self, old_size: usize, new_size: usize) -> AllocKind {
+        if new_size < old_size {
+            AllocKind::Shrink
+        } else {
+            self
+        }
+    }
+}
+
+/// Updates the total bytes allocated after a block was resized from `old_size` to `new_size`.
+#[inline]
+fn resize_total<L: StatsLogger>(logger: &L, old_size: usize, new_size: usize) -> usize {
+    if new_size >= old_size {
+        logger.inc_total_bytes_allocated(new_size - old_size)
+    } else {
+        logger.dec_total_bytes_allocated(old_size - new_size)
+    }
+}
+
 #[track_caller]
 #[inline]
 fn allocate<A: Alloc, L: StatsLogger, F: Fn(&A, Layout) -> Result<NonNull<u8>, AllocError>>(
@@ -563,6 +1158,7 @@ fn allocate<A: Alloc, L: StatsLogger, F: Fn(&A, Layout) -> Result<NonNull<u8>, A
                 },
                 kind,
                 total,
+                location: Location::caller(),
             }));
             Ok(ptr)
         }
@@ -575,6 +1171,7 @@ fn allocate<A: Alloc, L: StatsLogger, F: Fn(&A, Layout) -> Result<NonNull<u8>, A
                 },
                 kind,
                 total: slf.1.total(),
+                location: Location::caller(),
             }));
             Err(e)
         }
@@ -583,23 +1180,22 @@ fn allocate<A: Alloc, L: StatsLogger, F: Fn(&A, Layout) -> Result<NonNull<u8>, A
 
 #[track_caller]
 #[inline]
-fn grow<
+fn resize<
     A: Alloc,
     L: StatsLogger,
-    F: Fn(&A, NonNull<u8>, Layout, Layout) -> Result<NonNull<u8>, AllocError>,
+    F: FnOnce(&A, NonNull<u8>, Layout, Layout) -> Result<NonNull<u8>, AllocError>,
 >(
     slf: &Stats<A, L>,
-    grow: F,
+    resize: F,
     ptr: NonNull<u8>,
     old_layout: Layout,
     new_layout: Layout,
     kind: AllocKind,
 ) -> Result<NonNull<u8>, AllocError> {
-    match grow(&slf.0, ptr, old_layout, new_layout) {
+    let kind = kind.or_shrink(old_layout.size(), new_layout.size());
+    match resize(&slf.0, ptr, old_layout, new_layout) {
         Ok(new_ptr) => {
-            let total = slf
-                .1
-                .inc_total_bytes_allocated(new_layout.size().saturating_sub(old_layout.size()));
+            let total = resize_total(&slf.1, old_layout.size(), new_layout.size());
             slf.1.log(Succ(AllocStat::new_realloc(
                 ptr,
                 new_ptr.as_ptr(),
@@ -624,6 +1220,39 @@ fn grow<
     }
 }
 
+#[cfg(feature = "resize_in_place")]
+#[track_caller]
+#[inline]
+fn resize_in_place<A: Alloc, L: StatsLogger, F: FnOnce(&A) -> Result<(), AllocError>>(
+    slf: &Stats<A, L>,
+    resize: F,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_size: usize,
+    kind: AllocKind,
+) -> Result<(), AllocError> {
+    let kind = kind.or_shrink(old_layout.size(), new_size);
+    match resize(&slf.0) {
+        Ok(()) => {
+            let total = resize_total(&slf.1, old_layout.size(), new_size);
+            slf.1.log(Succ(AllocStat::new_in_place(
+                ptr, old_layout, new_size, kind, total,
+            )));
+            Ok(())
+        }
+        Err(e) => {
+            slf.1.log(Fail(AllocStat::new_in_place(
+                ptr,
+                old_layout,
+                new_size,
+                kind,
+                slf.1.total(),
+            )));
+            Err(e)
+        }
+    }
+}
+
 impl<A: Alloc, L: StatsLogger> Alloc for Stats<A, L> {
     #[track_caller]
     fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
@@ -648,6 +1277,7 @@ impl<A: Alloc, L: StatsLogger> Alloc for Stats<A, L> {
                 align: layout.align(),
             },
             total,
+            location: Location::caller(),
         }));
     }
 
@@ -658,7 +1288,7 @@ impl<A: Alloc, L: StatsLogger> Alloc for Stats<A, L> {
         old_layout: Layout,
         new_layout: Layout,
     ) -> Result<NonNull<u8>, AllocError> {
-        grow(
+        resize(
             self,
             |a, ptr, old, new| a.grow(ptr, old, new),
             ptr,
@@ -675,7 +1305,7 @@ impl<A: Alloc, L: StatsLogger> Alloc for Stats<A, L> {
         old_layout: Layout,
         new_layout: Layout,
     ) -> Result<NonNull<u8>, AllocError> {
-        grow(
+        resize(
             self,
             |a, ptr, old, new| a.grow_zeroed(ptr, old, new),
             ptr,
@@ -692,32 +1322,301 @@ impl<A: Alloc, L: StatsLogger> Alloc for Stats<A, L> {
         old_layout: Layout,
         new_layout: Layout,
     ) -> Result<NonNull<u8>, AllocError> {
-        match self.0.shrink(ptr, old_layout, new_layout) {
-            Ok(new_ptr) => {
-                let total = self
-                    .1
-                    .dec_total_bytes_allocated(old_layout.size().saturating_sub(new_layout.size()));
-                self.1.log(Succ(AllocStat::new_realloc(
-                    ptr,
-                    new_ptr.as_ptr(),
-                    old_layout,
-                    new_layout,
-                    AllocKind::Shrink,
-                    total,
-                )));
-                Ok(new_ptr)
-            }
-            Err(e) => {
-                self.1.log(Fail(AllocStat::new_realloc(
-                    ptr,
-                    null_mut(),
-                    old_layout,
-                    new_layout,
-                    AllocKind::Shrink,
-                    self.1.total(),
-                )));
-                Err(e)
-            }
+        resize(
+            self,
+            |a, ptr, old, new| a.shrink(ptr, old, new),
+            ptr,
+            old_layout,
+            new_layout,
+            AllocKind::Shrink,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        resize(
+            self,
+            |a, ptr, old, new| a.realloc(ptr, old, new),
+            ptr,
+            old_layout,
+            new_layout,
+            AllocKind::Uninitialized,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        resize(
+            self,
+            |a, ptr, old, new| a.realloc_zeroed(ptr, old, new),
+            ptr,
+            old_layout,
+            new_layout,
+            AllocKind::Zeroed,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        self.0.usable_size(ptr, layout)
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Whether this thread is inside a call to a [`Stats`] global allocator.
+    static IN_GLOBAL: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+/// Marks the current thread as inside a call to a [`Stats`] global allocator until dropped.
+struct GlobalGuard;
+
+impl GlobalGuard {
+    /// Enters a global allocator call, or returns `None` if the current thread is already in one.
+    ///
+    /// Without `std`, there's no way to tell, so this always succeeds.
+    #[cfg_attr(not(feature = "std"), allow(clippy::unnecessary_wraps))]
+    #[inline]
+    fn enter() -> Option<GlobalGuard> {
+        #[cfg(feature = "std")]
+        if IN_GLOBAL.try_with(|c| c.replace(true)).unwrap_or(true) {
+            return None;
+        }
+        Some(GlobalGuard)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for GlobalGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = IN_GLOBAL.try_with(|c| c.set(false));
+    }
+}
+
+/// Allows using [`Stats`] as the `#[global_allocator]`.
+///
+/// `A` must not allocate through the global allocator itself, so it can't be [`DefaultAlloc`];
+/// use e.g. `System` or `Jemalloc` instead.
+///
+/// With `std`, allocations made while the logger is running on the same thread, such as the ones
+/// by `StatCollectingLog` or `IOLog`, go straight to `A` and aren't logged or counted. The logger
+/// must free such blocks before `log` returns: a block it keeps, like the buffer of
+/// `StatCollectingLog`, is logged and subtracted from the total when it's freed later, so the
+/// total and peak drift low. Without `std`, the logger must not allocate at all.
+///
+/// `GlobalAlloc` methods can't be `#[track_caller]`, so the [location](AllocStat::location) of
+/// every operation logged through this impl points into this file rather than at the code that
+/// allocated.
+unsafe impl<A: Alloc, L: StatsLogger> GlobalAlloc for Stats<A, L> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match GlobalGuard::enter() {
+            Some(_guard) => Alloc::alloc(self, layout),
+            None => Alloc::alloc(&self.0, layout),
+        }
+        .map_or(null_mut(), NonNull::as_ptr)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let ptr = NonNull::new_unchecked(ptr);
+        match GlobalGuard::enter() {
+            Some(_guard) => Alloc::dealloc(self, ptr, layout),
+            None => Alloc::dealloc(&self.0, ptr, layout),
         }
     }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        match GlobalGuard::enter() {
+            Some(_guard) => Alloc::alloc_zeroed(self, layout),
+            None => Alloc::alloc_zeroed(&self.0, layout),
+        }
+        .map_or(null_mut(), NonNull::as_ptr)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let ptr = NonNull::new_unchecked(ptr);
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        match GlobalGuard::enter() {
+            Some(_guard) => Alloc::realloc(self, ptr, layout, new_layout),
+            None => Alloc::realloc(&self.0, ptr, layout, new_layout),
+        }
+        .map_or(null_mut(), NonNull::as_ptr)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace, L: StatsLogger> crate::in_place::ResizeInPlace
+    for Stats<A, L>
+{
+    #[track_caller]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.grow_in_place(ptr, old_layout, new_size),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Uninitialized,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn grow_in_place_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.grow_in_place_zeroed(ptr, old_layout, new_size),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Zeroed,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn grow_in_place_patterned<F: Fn(usize) -> u8 + Clone>(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        pattern: F,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.grow_in_place_patterned(ptr, old_layout, new_size, pattern),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Patterned,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn grow_in_place_filled(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        n: u8,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.grow_in_place_filled(ptr, old_layout, new_size, n),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Filled(n),
+        )
+    }
+
+    #[track_caller]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.shrink_in_place(ptr, old_layout, new_size),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Shrink,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn realloc_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.realloc_in_place(ptr, old_layout, new_size),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Uninitialized,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn realloc_in_place_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.realloc_in_place_zeroed(ptr, old_layout, new_size),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Zeroed,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn realloc_in_place_patterned<F: Fn(usize) -> u8 + Clone>(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        pattern: F,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.realloc_in_place_patterned(ptr, old_layout, new_size, pattern),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Patterned,
+        )
+    }
+
+    #[track_caller]
+    unsafe fn realloc_in_place_filled(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+        n: u8,
+    ) -> Result<(), AllocError> {
+        resize_in_place(
+            self,
+            |a| a.realloc_in_place_filled(ptr, old_layout, new_size, n),
+            ptr,
+            old_layout,
+            new_size,
+            AllocKind::Filled(n),
+        )
+    }
 }
@@ -0,0 +1,200 @@
+use crate::{error::AllocError, helpers::secure_zero, Alloc, AllocPattern, DefaultAlloc, Owns};
+use core::{alloc::Layout, ptr::NonNull};
+
+/// An allocator which wipes every block with [`secure_zero`] before it's deallocated, so secrets
+/// don't linger in freed memory.
+///
+/// Growing, shrinking and reallocating always move the block, since the inner allocator could
+/// otherwise move it without wiping the old copy. The old block is wiped like any other freed
+/// block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Zeroizing<A: Alloc = DefaultAlloc> {
+    /// The inner allocator.
+    alloc: A,
+}
+
+impl Zeroizing {
+    /// Creates a new zeroizing allocator backed by the default allocator.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Zeroizing {
+        Zeroizing::new_in(DefaultAlloc)
+    }
+}
+
+impl<A: Alloc> Zeroizing<A> {
+    /// Creates a new zeroizing allocator backed by `alloc`.
+    #[inline]
+    pub const fn new_in(alloc: A) -> Zeroizing<A> {
+        Zeroizing { alloc }
+    }
+
+    /// Gets a reference to the inner allocator.
+    #[inline]
+    pub const fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Unwraps the inner allocator.
+    #[inline]
+    pub fn into_inner(self) -> A {
+        self.alloc
+    }
+}
+
+impl<A: Alloc> Alloc for Zeroizing<A> {
+    #[track_caller]
+    #[inline]
+    fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.alloc.alloc(layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.alloc.alloc_zeroed(layout)
+    }
+
+    /// Wipes the block at `ptr`, then deallocates it.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        secure_zero(ptr.as_ptr(), layout.size());
+        self.alloc.dealloc(ptr, layout);
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::grow(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::None,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::grow(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::Zero,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::shrink(self, ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::ralloc(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::None,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        crate::ralloc(
+            self,
+            ptr,
+            old_layout,
+            new_layout,
+            AllocPattern::<fn(usize) -> u8>::Zero,
+        )
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        self.alloc.usable_size(ptr, layout)
+    }
+}
+
+impl<A: Owns> Owns for Zeroizing<A> {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        self.alloc.owns(ptr, layout)
+    }
+}
+
+#[cfg(feature = "resize_in_place")]
+impl<A: crate::in_place::ResizeInPlace> crate::in_place::ResizeInPlace for Zeroizing<A> {
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        self.alloc.grow_in_place(ptr, old_layout, new_size)
+    }
+
+    /// Wipes the bytes past `new_size`, then shrinks the given block in place.
+    ///
+    /// The bytes stay wiped even if shrinking fails.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `old_layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_size: usize,
+    ) -> Result<(), AllocError> {
+        if new_size < old_layout.size() {
+            secure_zero(ptr.as_ptr().add(new_size), old_layout.size() - new_size);
+        }
+        self.alloc.shrink_in_place(ptr, old_layout, new_size)
+    }
+}
@@ -47,6 +47,8 @@
 #![cfg_attr(feature = "specialization", feature(min_specialization))]
 #![cfg_attr(feature = "sized_hierarchy", feature(sized_hierarchy))]
 #![allow(unknown_lints, unsafe_op_in_unsafe_fn, internal_features)]
+// `extra_const` raises the MSRV to 1.83, so const-context MSRV lints don't apply.
+#![cfg_attr(feature = "extra_const", allow(clippy::incompatible_msrv))]
 #![deny(missing_docs)]
 
 // it is used, the compiler is just stupid
@@ -89,6 +91,9 @@ pub mod error;
 
 mod features;
 
+#[cfg(any(feature = "poison", feature = "checked", feature = "leak_check"))]
+pub(crate) mod spin;
+
 #[allow(unused_imports)]
 pub use features::*;
 
@@ -100,7 +105,7 @@ pub use external_alloc::*;
 
 use crate::{
     error::AllocError,
-    helpers::{null_q, zsl_check},
+    helpers::{nonnull_slice_from_raw_parts, null_q, zsl_check},
 };
 use alloc::alloc::{
     alloc as raw_all, alloc_zeroed as raw_allz, dealloc as de, GlobalAlloc, Layout,
@@ -117,19 +122,19 @@ pub struct DefaultAlloc;
 macro_rules! default_alloc_impl {
     ($ty:ty) => {
         impl Alloc for $ty {
-            #[cfg_attr(miri, track_caller)]
+            #[track_caller]
             #[inline]
             fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
                 zsl_check(layout, |layout| null_q(unsafe { raw_all(layout) }, layout))
             }
 
-            #[cfg_attr(miri, track_caller)]
+            #[track_caller]
             #[inline]
             fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
                 zsl_check(layout, |layout| null_q(unsafe { raw_allz(layout) }, layout))
             }
 
-            #[cfg_attr(miri, track_caller)]
+            #[track_caller]
             #[inline]
             unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
                 if layout.size() != 0 {
@@ -137,11 +142,21 @@ macro_rules! default_alloc_impl {
                 }
             }
         }
+
+        impl Owns for $ty {
+            const PRECISE: bool = false;
+
+            /// Always returns `true`, as the global allocator can't tell which blocks it allocated.
+            #[inline]
+            fn owns(&self, _: NonNull<u8>, _: Layout) -> bool {
+                true
+            }
+        }
     };
 }
 
 unsafe impl GlobalAlloc for DefaultAlloc {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         match Alloc::alloc(&self, layout) {
@@ -150,13 +165,13 @@ unsafe impl GlobalAlloc for DefaultAlloc {
         }
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         Alloc::dealloc(&self, NonNull::new_unchecked(ptr), layout);
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
         match Alloc::alloc_zeroed(&self, layout) {
@@ -165,7 +180,7 @@ unsafe impl GlobalAlloc for DefaultAlloc {
         }
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
         match Alloc::realloc(
@@ -200,7 +215,7 @@ pub trait Alloc {
     ///
     /// - [`AllocError::AllocFailed`] if allocation fails.
     /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         match self.alloc(layout) {
@@ -235,7 +250,7 @@ pub trait Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow(
         &self,
@@ -265,7 +280,7 @@ pub trait Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn grow_zeroed(
         &self,
@@ -294,7 +309,7 @@ pub trait Alloc {
     ///
     /// - `ptr` must point to a block of memory allocated using this allocator.
     /// - `old_layout` must describe exactly the same block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn shrink(
         &self,
@@ -319,7 +334,7 @@ pub trait Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc(
         &self,
@@ -348,7 +363,7 @@ pub trait Alloc {
     ///
     /// - `ptr` must point to a block previously allocated with this allocator.
     /// - `old_layout` must describe exactly that block.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn realloc_zeroed(
         &self,
@@ -364,6 +379,146 @@ pub trait Alloc {
             AllocPattern::<fn(usize) -> u8>::Zero,
         )
     }
+
+    /// Returns the number of bytes which are actually usable in the block at `ptr`.
+    ///
+    /// This is at least `layout.size()`, and may be larger if the allocator rounded the request up
+    /// (e.g. to a size class). The default implementation returns `layout.size()`.
+    ///
+    /// Implementations which return more than `layout.size()` must accept a layout with the same
+    /// alignment and any size between `layout.size()` and the returned size as describing the
+    /// block in later calls.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, _: NonNull<u8>, layout: Layout) -> usize {
+        layout.size()
+    }
+
+    /// Attempts to allocate a block of memory fitting the given [`Layout`], returning it along
+    /// with its usable length.
+    ///
+    /// The returned length is at least `layout.size()`, and the entire block may be used. A layout
+    /// with `layout.align()` and any size up to the returned length describes the block.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
+    #[track_caller]
+    #[inline]
+    fn alloc_sized(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        match self.alloc(layout) {
+            Ok(ptr) => Ok(unsafe { sized(self, ptr, layout) }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Attempts to allocate a zeroed block of memory fitting the given [`Layout`], returning it
+    /// along with its usable length.
+    ///
+    /// Only the first `layout.size()` bytes are guaranteed to be zeroed.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::ZeroSizedLayout`] if `layout` has a size of zero.
+    #[track_caller]
+    #[inline]
+    fn alloc_zeroed_sized(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        match self.alloc_zeroed(layout) {
+            Ok(ptr) => Ok(unsafe { sized(self, ptr, layout) }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Grow the given block to a new, larger layout, returning it along with its usable length.
+    ///
+    /// # Errors
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::GrowSmallerNewLayout`] if `new_layout.size() < old_layout.size()`.
+    /// - [`AllocError::ZeroSizedLayout`] if `new_layout` has a size of zero.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `old_layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_sized(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        match self.grow(ptr, old_layout, new_layout) {
+            Ok(ptr) => Ok(sized(self, ptr, new_layout)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Shrink the given block to a new, smaller layout, returning it along with its usable length.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::ShrinkBiggerNewLayout`] if `new_layout.size() > old_layout.size()`.
+    /// - [`AllocError::ZeroSizedLayout`] if `new_layout` has a size of zero.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block of memory allocated using this allocator.
+    /// - `old_layout` must describe exactly the same block.
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink_sized(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        match self.shrink(ptr, old_layout, new_layout) {
+            Ok(ptr) => Ok(sized(self, ptr, new_layout)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Reallocate a block, growing or shrinking as needed, returning it along with its usable
+    /// length.
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocError::AllocFailed`] if allocation fails.
+    /// - [`AllocError::ZeroSizedLayout`] if `new_layout` has a size of zero.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to a block previously allocated with this allocator.
+    /// - `old_layout` must describe exactly that block.
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_sized(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        match self.realloc(ptr, old_layout, new_layout) {
+            Ok(ptr) => Ok(sized(self, ptr, new_layout)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Internal helper to pair a freshly (re)allocated block with its usable length.
+#[track_caller]
+#[inline]
+unsafe fn sized<A: Alloc + ?Sized>(a: &A, ptr: NonNull<u8>, layout: Layout) -> NonNull<[u8]> {
+    nonnull_slice_from_raw_parts(ptr, a.usable_size(ptr, layout))
 }
 
 #[cfg(feature = "nightly")]
@@ -372,7 +527,7 @@ pub(crate) mod nightly {
     use crate::{
         error::AllocError,
         helpers::{null_q, zsl_check},
-        Alloc, DefaultAlloc,
+        Alloc, DefaultAlloc, Owns,
     };
     use alloc::alloc::{
         alloc as raw_all, alloc_zeroed as raw_allz, dealloc as de, Allocator, Global, Layout,
@@ -380,25 +535,25 @@ pub(crate) mod nightly {
     use core::{alloc::AllocError as AllocatorError, ptr::NonNull};
 
     unsafe impl Allocator for DefaultAlloc {
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocatorError> {
             Allocator::allocate(&Global, layout)
         }
 
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocatorError> {
             Allocator::allocate_zeroed(&Global, layout)
         }
 
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
             Allocator::deallocate(&Global, ptr.cast(), layout);
         }
 
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         unsafe fn grow(
             &self,
@@ -409,7 +564,7 @@ pub(crate) mod nightly {
             Allocator::grow(&Global, ptr.cast(), old_layout, new_layout)
         }
 
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         unsafe fn grow_zeroed(
             &self,
@@ -420,7 +575,7 @@ pub(crate) mod nightly {
             Allocator::grow_zeroed(&Global, ptr.cast(), old_layout, new_layout)
         }
 
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         unsafe fn shrink(
             &self,
@@ -436,27 +591,129 @@ pub(crate) mod nightly {
 }
 
 impl<A: Alloc + ?Sized> Alloc for &A {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         (**self).alloc(layout)
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         (**self).alloc_zeroed(layout)
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
         (**self).dealloc(ptr, layout);
     }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).grow(ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).grow_zeroed(ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).shrink(ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).realloc(ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn realloc_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).realloc_zeroed(ptr, old_layout, new_layout)
+    }
+
+    #[track_caller]
+    #[inline]
+    unsafe fn usable_size(&self, ptr: NonNull<u8>, layout: Layout) -> usize {
+        (**self).usable_size(ptr, layout)
+    }
+}
+
+/// An allocator which can tell whether a block of memory was allocated by it.
+///
+/// Region-based allocators, like `BumpAlloc` and `Pool`, answer precisely. General-purpose
+/// allocators which can't tell answer conservatively: they claim to own every block, and set
+/// [`PRECISE`](Owns::PRECISE) to `false`.
+///
+/// Combinators which route blocks back to the allocator they came from need this. `Fallback`
+/// asks its primary allocator, so the primary must be precise, while the secondary allocator may
+/// be anything. Combinators which route by layout alone, like `Segregate`, don't need it.
+pub trait Owns: Alloc {
+    /// Whether [`owns`](Owns::owns) answers precisely, rather than returning `true` for blocks it
+    /// can't tell apart.
+    const PRECISE: bool = true;
+
+    /// Returns whether the block at `ptr`, described by `layout`, was allocated by this allocator.
+    ///
+    /// This only inspects addresses, so it may be called with any pointer. The answer is only
+    /// meaningful for live blocks.
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool;
+}
+
+impl<A: Owns + ?Sized> Owns for &A {
+    const PRECISE: bool = A::PRECISE;
+
+    #[inline]
+    fn owns(&self, ptr: NonNull<u8>, layout: Layout) -> bool {
+        (**self).owns(ptr, layout)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Owns for std::alloc::System {
+    const PRECISE: bool = false;
+
+    /// Always returns `true`, as the system allocator can't tell which blocks it allocated.
+    #[inline]
+    fn owns(&self, _: NonNull<u8>, _: Layout) -> bool {
+        true
+    }
 }
 
 #[cfg(feature = "std")]
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 #[inline]
 fn zsl_check_alloc<A: GlobalAlloc>(
     a: &A,
@@ -470,19 +727,19 @@ fn zsl_check_alloc<A: GlobalAlloc>(
 
 #[cfg(feature = "std")]
 impl Alloc for std::alloc::System {
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         zsl_check_alloc(self, layout, GlobalAlloc::alloc)
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
         zsl_check_alloc(self, layout, GlobalAlloc::alloc_zeroed)
     }
 
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
         GlobalAlloc::dealloc(self, ptr.as_ptr(), layout);
@@ -492,7 +749,7 @@ impl Alloc for std::alloc::System {
 /// Internal helper to grow the allocation at `ptr` by deallocating using `old_layout` and
 /// reallocating using `new_layout`, filling new bytes using `pattern.`
 #[inline]
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 pub(crate) unsafe fn grow<A: Alloc + ?Sized, F: Fn(usize) -> u8 + Clone>(
     a: &A,
     ptr: NonNull<u8>,
@@ -519,7 +776,7 @@ pub(crate) unsafe fn grow<A: Alloc + ?Sized, F: Fn(usize) -> u8 + Clone>(
 /// Internal helper to shrink the allocation at `ptr` by deallocating using `old_layout` and
 /// reallocating using `new_layout`.
 #[inline]
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 pub(crate) unsafe fn shrink<A: Alloc + ?Sized>(
     a: &A,
     ptr: NonNull<u8>,
@@ -550,7 +807,7 @@ pub(crate) unsafe fn shrink<A: Alloc + ?Sized>(
 /// This function does not check for layout validity. `new_layout.size()` should be greater than
 /// `old_layout.size()`.
 #[inline]
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 #[allow(clippy::needless_pass_by_value)]
 unsafe fn grow_unchecked<A: Alloc + ?Sized, F: Fn(usize) -> u8 + Clone>(
     a: &A,
@@ -587,7 +844,7 @@ unsafe fn grow_unchecked<A: Alloc + ?Sized, F: Fn(usize) -> u8 + Clone>(
 /// This function does not check for layout validity. `new_layout.size()` should be greater than
 /// `old_layout.size()`.
 #[inline]
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 unsafe fn shrink_unchecked<A: Alloc + ?Sized>(
     a: &A,
     ptr: NonNull<u8>,
@@ -604,7 +861,7 @@ unsafe fn shrink_unchecked<A: Alloc + ?Sized>(
 }
 
 /// Helper for realloc to reduce repetition.
-#[cfg_attr(miri, track_caller)]
+#[track_caller]
 #[inline]
 pub(crate) unsafe fn ralloc<A: Alloc + ?Sized, F: Fn(usize) -> u8 + Clone>(
     a: &A,
@@ -652,10 +909,11 @@ pub mod helpers {
     };
     use core::{
         alloc::Layout,
-        mem::{align_of, transmute, ManuallyDrop},
+        mem::{align_of, size_of, transmute, ManuallyDrop},
         num::NonZeroUsize,
         ops::Deref,
         ptr::{eq as peq, NonNull},
+        sync::atomic::{compiler_fence, Ordering},
     };
 
     // yet again.
@@ -708,6 +966,25 @@ pub mod helpers {
     }
 
 
+    /// Zeroes `count * size_of::<T>()` bytes at `ptr` using volatile writes followed by a compiler
+    /// fence, so the writes aren't optimized away even if the memory is never read again.
+    ///
+    /// Use this over [`write_bytes`](core::ptr::write_bytes) to erase secrets before memory is
+    /// deallocated.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must be valid for writes of `count` `T`s.
+    #[track_caller]
+    #[inline]
+    pub unsafe fn secure_zero<T>(ptr: *mut T, count: usize) {
+        let bytes = ptr as *mut u8;
+        for i in 0..count * size_of::<T>() {
+            bytes.add(i).write_volatile(0);
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+
     /// Converts a possibly null pointer into a [`NonNull`] result.
     #[inline]
     pub(crate) fn null_q<T>(ptr: *mut T, layout: Layout) -> Result<NonNull<u8>, AllocError> {
@@ -734,7 +1011,7 @@ pub mod helpers {
 
     #[cfg(any(feature = "alloc_slice", feature = "owned"))]
     /// Deallocates `n` elements of type `T` at `ptr` using a reference to an `A`.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub(crate) unsafe fn dealloc_n<T, A: Alloc + ?Sized>(a: &A, ptr: NonNull<T>, n: usize) {
         // Here, we assume the layout is valid as it was presumably used to allocate previously.
@@ -745,23 +1022,31 @@ pub mod helpers {
     }
 
     #[cfg(any(feature = "alloc_slice", feature = "owned"))]
-    /// Allocates a slice of `len` elements of type `T` using the given reference to an `A` and an
-    /// allocation function pointer.
-    #[cfg_attr(miri, track_caller)]
+    /// Allocates a slice of `len` elements of type `T` using the given reference to an `A`, zeroing
+    /// it if `zeroed` is true.
+    ///
+    /// This takes a flag rather than a function pointer, as calls through function pointers don't
+    /// pass on the caller's location.
+    #[track_caller]
     #[inline]
     pub(crate) fn alloc_slice<T, A: Alloc + ?Sized>(
         a: &A,
         len: usize,
-        alloc: fn(&A, Layout) -> Result<NonNull<u8>, AllocError>,
+        zeroed: bool,
     ) -> Result<NonNull<[T]>, AllocError> {
         let layout = layout_or_sz_align::<T>(len)
             .map_err(|(sz, align)| AllocError::LayoutError(sz, align))?;
-        alloc(a, layout).map(|ptr| nonnull_slice_from_raw_parts(ptr.cast(), len))
+        let ptr = if zeroed {
+            a.alloc_zeroed(layout)
+        } else {
+            a.alloc(layout)
+        }?;
+        Ok(nonnull_slice_from_raw_parts(ptr.cast(), len))
     }
 
     #[cfg(all(any(feature = "alloc_ext", feature = "owned"), feature = "metadata"))]
     /// Allocates space for a copy of the value behind `data`, and copies it into the new memory.
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub(crate) unsafe fn alloc_copy_ptr_to_unchecked<T: ?Sized, A: Alloc + ?Sized>(
         a: &A,
@@ -777,7 +1062,7 @@ pub mod helpers {
     }
 
     #[cfg(any(feature = "alloc_ext", feature = "owned"))]
-    #[cfg_attr(miri, track_caller)]
+    #[track_caller]
     #[inline]
     pub(crate) fn alloc_write<T, A: Alloc + ?Sized>(
         a: &A,
@@ -897,7 +1182,7 @@ pub mod helpers {
 
         #[cfg(feature = "extra_const")]
         /// Initializes the value by writing to the contained pointer.
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         pub const fn init(&self, elem: T)
         where
@@ -910,7 +1195,7 @@ pub mod helpers {
 
         #[cfg(not(feature = "extra_const"))]
         /// Initializes the value by writing to the contained pointer.
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         #[inline]
         pub fn init(&self, elem: T)
         where
@@ -933,7 +1218,7 @@ pub mod helpers {
     }
 
     impl<T: ?Sized, A: Alloc + ?Sized> Drop for AllocGuard<'_, T, A> {
-        #[cfg_attr(miri, track_caller)]
+        #[track_caller]
         fn drop(&mut self) {
             unsafe {
                 self.alloc.dealloc(self.ptr.cast::<u8>(), self.ptr.layout());
@@ -20,6 +20,17 @@ FEATURES = [
     "owned",
     "drop_for_owned",
     "zero_drop_for_owned",
+    "bump",
+    "pool",
+    "fallback",
+    "segregate",
+    "limited",
+    "failing",
+    "guarded",
+    "poison",
+    "checked",
+    "leak_check",
+    "zeroizing",
     "jemalloc",
     "mimalloc",
 ]
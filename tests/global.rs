@@ -0,0 +1,95 @@
+// these tests install `Stats` as the global allocator, so they live in their own binary.
+use memapi::stats::{AllocRes, Counters, Stats, StatsLogger, StatsSnapshot};
+use std::{
+    alloc::System,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A logger which allocates while logging, like `IOLog` does.
+struct AllocatingLog {
+    counters: Counters,
+    logged: AtomicUsize,
+}
+
+impl StatsLogger for AllocatingLog {
+    fn log(&self, stat: AllocRes) {
+        // this allocates through the global allocator, so it re-enters `Stats`.
+        let line = stat.to_string();
+        assert!(!line.is_empty());
+        self.logged.fetch_add(1, Ordering::SeqCst);
+        self.counters.log(stat);
+    }
+
+    fn inc_total_bytes_allocated(&self, bytes: usize) -> usize {
+        self.counters.inc_total_bytes_allocated(bytes)
+    }
+
+    fn dec_total_bytes_allocated(&self, bytes: usize) -> usize {
+        self.counters.dec_total_bytes_allocated(bytes)
+    }
+
+    fn total(&self) -> usize {
+        self.counters.total()
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Stats<System, AllocatingLog> = Stats::new_in(
+    System,
+    AllocatingLog {
+        counters: Counters::new(),
+        logged: AtomicUsize::new(0),
+    },
+);
+
+fn snapshot() -> StatsSnapshot {
+    GLOBAL.1.counters.snapshot()
+}
+
+// other test threads allocate too, so these only check lower bounds.
+
+#[test]
+fn counts_boxes() {
+    let before = snapshot();
+    let logged = GLOBAL.1.logged.load(Ordering::SeqCst);
+
+    let boxed = black_box(Box::new([7u8; 4096]));
+    let during = snapshot();
+    assert!(during.allocs > before.allocs);
+    assert!(during.peak >= 4096);
+    drop(boxed);
+
+    assert!(snapshot().frees > before.frees);
+    assert!(GLOBAL.1.logged.load(Ordering::SeqCst) >= logged + 2);
+}
+
+#[test]
+fn counts_reallocs() {
+    let before = snapshot();
+
+    let mut v: Vec<u64> = Vec::with_capacity(4);
+    v.extend(0..1024);
+    v.truncate(1);
+    v.shrink_to_fit();
+    assert_eq!(black_box(v), [0]);
+
+    let after = snapshot();
+    assert!(after.grows > before.grows);
+    assert!(after.shrinks > before.shrinks);
+}
+
+#[test]
+fn allocating_logger_does_not_recurse() {
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let s = black_box(format!("thread {i}"));
+                s.len()
+            })
+        })
+        .collect();
+    for t in threads {
+        assert!(t.join().unwrap() >= 8);
+    }
+}
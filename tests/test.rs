@@ -69,6 +69,43 @@ fn test_shrink_and_error_cases() {
     }
 }
 
+#[test]
+fn test_sized_variants_report_requested_size() {
+    let allocator = DefaultAlloc;
+    let layout = Layout::from_size_align(24, 8).unwrap();
+    let block = allocator.alloc_sized(layout).unwrap();
+    assert_eq!(unsafe { block.as_ref() }.len(), layout.size());
+
+    let new = Layout::from_size_align(48, 8).unwrap();
+    let grown = unsafe { allocator.grow_sized(block.cast(), layout, new).unwrap() };
+    assert_eq!(unsafe { grown.as_ref() }.len(), new.size());
+
+    let shrunk = unsafe { allocator.shrink_sized(grown.cast(), new, layout).unwrap() };
+    assert_eq!(unsafe { shrunk.as_ref() }.len(), layout.size());
+
+    unsafe {
+        allocator.dealloc(shrunk.cast(), layout);
+    }
+}
+
+#[test]
+fn test_owns_is_conservative_for_global() {
+    use memapi::Owns;
+
+    let allocator = DefaultAlloc;
+    let layout = Layout::new::<u64>();
+    let ptr = allocator.alloc(layout).unwrap();
+    let stack = 0u64;
+    assert!(allocator.owns(ptr, layout));
+    // it can't tell, so it claims everything
+    assert!(allocator.owns(core::ptr::NonNull::from(&stack).cast(), layout));
+    let precise = <DefaultAlloc as Owns>::PRECISE;
+    assert!(!precise);
+    unsafe {
+        allocator.dealloc(ptr, layout);
+    }
+}
+
 #[test]
 fn test_pad_layout_functions() {
     let layout = Layout::from_size_align(10, 4).unwrap();
@@ -304,11 +341,16 @@ mod alloc_slice_tests {
 mod stats_gathering_tests {
     use core::{
         alloc::Layout,
+        ptr::NonNull,
         sync::atomic::{AtomicUsize, Ordering},
     };
     use memapi::{
-        stats::{FmtLog, Stats},
-        Alloc,
+        error::AllocError,
+        stats::{
+            AllocRes, CallSite, CallSiteLog, Counters, FmtLog, Histogram, StatCollectingLog, Stats,
+            StatsLogger, StatsSnapshot, HISTOGRAM_BUCKETS,
+        },
+        Alloc, DefaultAlloc,
     };
 
     #[test]
@@ -340,27 +382,453 @@ mod stats_gathering_tests {
 
         let layout = Layout::from_size_align(16, 8).unwrap();
 
-        let ptr = stats_alloc.alloc(layout).unwrap();
+        let (ptr, alloc_line) = (stats_alloc.alloc(layout).unwrap(), line!());
 
-        unsafe {
-            stats_alloc.dealloc(ptr, layout);
-        }
+        let free_line = line!();
+        unsafe { stats_alloc.dealloc(ptr, layout) };
 
+        let log = logger.get_log();
+        let lines: Vec<(&str, &str)> = log.lines().map(|l| l.split_once(": ").unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0]
+            .0
+            .starts_with(&format!("{}:{alloc_line}:", file!())));
+        assert!(lines[1]
+            .0
+            .starts_with(&format!("{}:{}:", file!(), free_line + 1)));
         assert_eq!(
-            *logger.get_log(),
+            lines[0].1,
             format!(
                 "Successful initial allocation of 16 bytes with alignment 8 at {ptr:p}, and newly \
-                allocated bytes being uninitialized. (16 total bytes allocated)\nDeallocation of \
-                16 bytes with alignment 8 at {ptr:p}. (0 total bytes allocated)\n"
+                allocated bytes being uninitialized. (16 total bytes allocated)"
+            )
+        );
+        assert_eq!(
+            lines[1].1,
+            format!(
+                "Deallocation of 16 bytes with alignment 8 at {ptr:p}. (0 total bytes allocated)"
             )
         );
     }
+
+    #[test]
+    fn atomic_total_is_race_free() {
+        use std::{sync::Arc, thread};
+
+        let stats = Arc::new(Stats::new(AtomicUsize::new(0)));
+        let layout = Layout::new::<[u8; 24]>();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let stats = Arc::clone(&stats);
+                thread::spawn(move || {
+                    for _ in 0..500 {
+                        let ptr = stats.alloc(layout).unwrap();
+                        unsafe { stats.dealloc(ptr, layout) };
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(stats.1.load(Ordering::SeqCst), 0);
+        // freeing more than was allocated doesn't wrap around
+        assert_eq!(stats.1.dec_total_bytes_allocated(8), 0);
+    }
+
+    #[test]
+    fn counters_track_ops_and_peak() {
+        let counters = Counters::new();
+        let stats = Stats::new(&counters);
+        let small = Layout::new::<[u8; 16]>();
+        let big = Layout::new::<[u8; 64]>();
+        unsafe {
+            let a = stats.alloc(small).unwrap();
+            let b = stats.alloc(small).unwrap();
+            let b = stats.grow(b, small, big).unwrap();
+            let b = stats.shrink(b, big, small).unwrap();
+            assert!(stats
+                .alloc(Layout::from_size_align(usize::MAX / 2, 1).unwrap())
+                .is_err());
+            stats.dealloc(a, small);
+            stats.dealloc(b, small);
+        }
+        assert_eq!(
+            counters.snapshot(),
+            StatsSnapshot {
+                total: 0,
+                peak: 80,
+                allocs: 2,
+                frees: 2,
+                grows: 1,
+                shrinks: 1,
+                failures: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn counters_are_consistent_across_threads() {
+        use std::{sync::Arc, thread};
+
+        let counters = Arc::new(Counters::new());
+        let layout = Layout::new::<u64>();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let stats = Stats::new(Arc::clone(&counters));
+                thread::spawn(move || {
+                    for _ in 0..500 {
+                        let ptr = stats.alloc(layout).unwrap();
+                        unsafe { stats.dealloc(ptr, layout) };
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let snapshot = counters.snapshot();
+        assert_eq!(
+            (snapshot.allocs, snapshot.frees, snapshot.total),
+            (2000, 2000, 0)
+        );
+        assert!(snapshot.peak >= 8 && snapshot.peak <= 32);
+    }
+
+    #[test]
+    fn unlogged_byte_updates_do_not_block_snapshots() {
+        let counters = Counters::new();
+        assert_eq!(counters.inc_total_bytes_allocated(24), 24);
+        assert_eq!(counters.dec_total_bytes_allocated(8), 16);
+        assert_eq!(counters.total(), 16);
+        // only logged operations show up in snapshots
+        assert_eq!(counters.snapshot(), StatsSnapshot::default());
+    }
+
+    #[test]
+    fn snapshots_never_split_operations() {
+        use std::{
+            sync::{atomic::AtomicBool, Arc},
+            thread,
+        };
+
+        let counters = Arc::new(Counters::new());
+        let done = Arc::new(AtomicBool::new(false));
+        let layout = Layout::new::<u64>();
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let stats = Stats::new(Arc::clone(&counters));
+                thread::spawn(move || {
+                    for _ in 0..2000 {
+                        let ptr = stats.alloc(layout).unwrap();
+                        unsafe { stats.dealloc(ptr, layout) };
+                    }
+                })
+            })
+            .collect();
+        let reader = {
+            let counters = Arc::clone(&counters);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                while !done.load(Ordering::SeqCst) {
+                    let snapshot = counters.snapshot();
+                    assert_eq!(snapshot.total, 8 * (snapshot.allocs - snapshot.frees));
+                }
+            })
+        };
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+        reader.join().unwrap();
+    }
+
+    #[test]
+    fn histogram_buckets_sizes() {
+        let histogram = Histogram::new();
+        let stats = Stats::new(&histogram);
+        let ptrs: Vec<_> = [1, 3, 4, 5, 100, 100]
+            .iter()
+            .map(|&size| {
+                let layout = Layout::from_size_align(size, 1).unwrap();
+                (stats.alloc(layout).unwrap(), layout)
+            })
+            .collect();
+        assert_eq!(histogram.bucket_of(4), 2);
+        assert_eq!(histogram.bucket_of(5), 3);
+
+        let used: Vec<_> = histogram.buckets().filter(|b| b.cumulative != 0).collect();
+        let ranges: Vec<_> = used.iter().map(|b| (b.min, b.max, b.live)).collect();
+        assert_eq!(ranges, [(0, 1, 1), (3, 4, 2), (5, 8, 1), (65, 128, 2)]);
+
+        for (ptr, layout) in ptrs {
+            unsafe { stats.dealloc(ptr, layout) };
+        }
+        assert!(histogram.buckets().all(|b| b.live == 0));
+        assert_eq!(histogram.bucket(7).cumulative, 2);
+        assert_eq!(histogram.bucket(HISTOGRAM_BUCKETS - 1).max, usize::MAX);
+        assert_eq!(
+            histogram.to_string(),
+            "size histogram (live / cumulative blocks):\n  0..=1 bytes: 0 / 1\n  3..=4 bytes: 0 / \
+            2\n  5..=8 bytes: 0 / 1\n  65..=128 bytes: 0 / 2"
+        );
+    }
+
+    #[test]
+    fn histogram_linear_buckets_and_resizes() {
+        let histogram = Histogram::linear(16);
+        let stats = Stats::new(&histogram);
+        let small = Layout::new::<[u8; 8]>();
+        let big = Layout::new::<[u8; 40]>();
+        unsafe {
+            let ptr = stats.alloc(small).unwrap();
+            let ptr = stats.grow(ptr, small, big).unwrap();
+            assert_eq!(
+                (histogram.bucket(0).live, histogram.bucket(0).cumulative),
+                (0, 1)
+            );
+            assert_eq!((histogram.bucket(2).min, histogram.bucket(2).max), (32, 47));
+            assert_eq!(histogram.bucket(2).live, 1);
+            stats.dealloc(ptr, big);
+        }
+        assert_eq!(histogram.bucket_of(usize::MAX), HISTOGRAM_BUCKETS - 1);
+        assert_eq!(histogram.total(), 0);
+    }
+
+    #[test]
+    fn histogram_zero_width_is_one() {
+        use memapi::stats::BucketKind;
+
+        let histogram = Histogram::with_buckets(BucketKind::Linear(0));
+        assert_eq!(histogram.kind(), BucketKind::Linear(1));
+        let stats = Stats::new(&histogram);
+        let layout = Layout::new::<[u8; 3]>();
+        let ptr = stats.alloc(layout).unwrap();
+        assert_eq!(histogram.bucket(3).live, 1);
+        unsafe { stats.dealloc(ptr, layout) };
+        assert_eq!(Histogram::linear(0).kind(), BucketKind::Linear(1));
+    }
+
+    #[test]
+    fn stats_record_call_sites() {
+        let log = StatCollectingLog::new();
+        let stats = Stats::new(&log);
+        let layout = Layout::new::<u64>();
+        let (ptr, line) = (stats.alloc(layout).unwrap(), line!());
+        unsafe { stats.dealloc(ptr, layout) };
+
+        let results = log.results.lock().unwrap();
+        let (AllocRes::Succ(stat) | AllocRes::Fail(stat)) = &results[0];
+        assert_eq!(stat.location().file(), file!());
+        assert_eq!(stat.location().line(), line);
+        let (AllocRes::Succ(stat) | AllocRes::Fail(stat)) = &results[1];
+        assert_eq!(stat.location().line(), line + 1);
+    }
+
+    #[test]
+    fn stats_record_call_sites_through_references() {
+        let log = StatCollectingLog::new();
+        let stats = Stats::new(&log);
+        let layout = Layout::new::<u64>();
+        let by_ref = &&stats;
+        let (ptr, line) = (by_ref.alloc(layout).unwrap(), line!());
+        unsafe { by_ref.dealloc(ptr, layout) };
+
+        #[cfg(feature = "owned")]
+        let owned_line = {
+            use memapi::owned::OwnedBuf;
+
+            let (mut buf, line) = (OwnedBuf::<u32, _>::new_in(4, &stats).unwrap(), line!());
+            buf.try_extend([1, 2, 3, 4]).unwrap();
+            buf.init_next_grow(5).unwrap();
+            #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+            buf.drop_and_dealloc();
+            line
+        };
+
+        let results = log.results.lock().unwrap();
+        // drop glue can't pass on a caller's location, so deallocating on drop is left out
+        let tracked = if cfg!(any(
+            feature = "drop_for_owned",
+            feature = "zero_drop_for_owned"
+        )) {
+            results.len() - 1
+        } else {
+            results.len()
+        };
+        for res in &results[..tracked] {
+            let (AllocRes::Succ(stat) | AllocRes::Fail(stat)) = res;
+            assert_eq!(stat.location().file(), file!(), "{}", stat.location());
+        }
+        let (AllocRes::Succ(stat) | AllocRes::Fail(stat)) = &results[0];
+        assert_eq!(stat.location().line(), line);
+        #[cfg(feature = "owned")]
+        {
+            let (AllocRes::Succ(stat) | AllocRes::Fail(stat)) = &results[2];
+            assert_eq!(stat.location().line(), owned_line);
+            assert_eq!(results.len(), 5);
+        }
+    }
+
+    #[test]
+    fn call_site_log_aggregates_by_line() {
+        let log = CallSiteLog::new();
+        let stats = Stats::new(&log);
+        let small = Layout::new::<[u8; 16]>();
+        let big = Layout::new::<[u8; 128]>();
+
+        let mut ptrs = Vec::new();
+        let alloc_line = line!() + 2;
+        for _ in 0..3 {
+            ptrs.push(stats.alloc(small).unwrap());
+        }
+        let grow_line = line!() + 1;
+        let grown = unsafe { stats.grow(ptrs.pop().unwrap(), small, big).unwrap() };
+        let free_line = line!() + 2;
+        for ptr in ptrs {
+            unsafe { stats.dealloc(ptr, small) };
+        }
+        unsafe { stats.dealloc(grown, big) };
+
+        let sites = log.sites();
+        assert_eq!(sites.len(), 4);
+        assert_eq!(
+            sites[0],
+            CallSite {
+                file: file!(),
+                line: grow_line,
+                ops: 1,
+                failures: 0,
+                allocated: 112,
+                freed: 0,
+            }
+        );
+        assert_eq!(
+            sites[1],
+            CallSite {
+                file: file!(),
+                line: alloc_line,
+                ops: 3,
+                failures: 0,
+                allocated: 48,
+                freed: 0,
+            }
+        );
+        let frees = sites.iter().find(|s| s.line == free_line).unwrap();
+        assert_eq!((frees.ops, frees.freed), (2, 32));
+        assert_eq!(log.total(), 0);
+        assert!(log
+            .to_string()
+            .contains(&format!("{}:{grow_line}: 112 bytes allocated", file!())));
+    }
+
+    /// Counts calls to its own `realloc`, which moves blocks through the default allocator.
+    #[derive(Default)]
+    struct NativeRealloc {
+        reallocs: AtomicUsize,
+    }
+
+    impl Alloc for NativeRealloc {
+        fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+            DefaultAlloc.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+            DefaultAlloc.dealloc(ptr, layout);
+        }
+
+        unsafe fn realloc(
+            &self,
+            ptr: NonNull<u8>,
+            old_layout: Layout,
+            new_layout: Layout,
+        ) -> Result<NonNull<u8>, AllocError> {
+            self.reallocs.fetch_add(1, Ordering::SeqCst);
+            DefaultAlloc.realloc(ptr, old_layout, new_layout)
+        }
+    }
+
+    #[test]
+    fn stats_forward_realloc() {
+        let counters = Counters::new();
+        let stats = Stats::new_in(NativeRealloc::default(), &counters);
+        let small = Layout::new::<[u8; 16]>();
+        let big = Layout::new::<[u8; 64]>();
+        unsafe {
+            let ptr = stats.alloc(small).unwrap();
+            let ptr = stats.realloc(ptr, small, big).unwrap();
+            assert_eq!(counters.total(), 64);
+            let ptr = stats.realloc(ptr, big, small).unwrap();
+            assert_eq!(counters.total(), 16);
+            stats.dealloc(ptr, small);
+        }
+        assert_eq!(stats.0.reallocs.load(Ordering::SeqCst), 2);
+        let snapshot = counters.snapshot();
+        assert_eq!(
+            (snapshot.grows, snapshot.shrinks, snapshot.total),
+            (1, 1, 0)
+        );
+    }
+
+    #[cfg(all(feature = "resize_in_place", feature = "bump"))]
+    #[test]
+    fn stats_log_in_place_resizes() {
+        use memapi::{
+            bump::BumpAlloc,
+            in_place::ResizeInPlace,
+            stats::{AllocKind, AllocStat},
+        };
+
+        let log = StatCollectingLog::new();
+        let stats = Stats::new_in(BumpAlloc::new(), &log);
+        let layout = Layout::new::<[u8; 16]>();
+        unsafe {
+            let ptr = stats.alloc(layout).unwrap();
+            stats.grow_in_place_zeroed(ptr, layout, 48).unwrap();
+            assert_eq!(log.total(), 48);
+            stats
+                .shrink_in_place(ptr, Layout::new::<[u8; 48]>(), 8)
+                .unwrap();
+            assert_eq!(log.total(), 8);
+            let other = stats.alloc(layout).unwrap();
+            assert!(stats
+                .grow_in_place(ptr, Layout::new::<[u8; 8]>(), 64)
+                .is_err());
+            assert_eq!(log.total(), 24);
+            stats.dealloc(other, layout);
+            stats.dealloc(ptr, Layout::new::<[u8; 8]>());
+        }
+
+        let results = log.results.lock().unwrap();
+        match &results[1] {
+            AllocRes::Succ(AllocStat::InPlace { info, kind, .. }) => {
+                assert_eq!(info.old.ptr, info.new.ptr);
+                assert_eq!((info.old.size, info.new.size), (16, 48));
+                assert!(matches!(kind, AllocKind::Zeroed));
+            }
+            other => panic!("expected an in-place resize, got {other:?}"),
+        }
+        assert!(matches!(
+            results[2],
+            AllocRes::Succ(AllocStat::InPlace {
+                kind: AllocKind::Shrink,
+                ..
+            })
+        ));
+        assert!(matches!(
+            results[4],
+            AllocRes::Fail(AllocStat::InPlace { .. })
+        ));
+        assert!(results[1]
+            .to_string()
+            .contains("Successful in-place resize from 16->48 bytes"));
+    }
 }
 
 #[cfg(feature = "owned")]
 mod owned_tests {
     use memapi::{
-        owned::{OwnedBuf, VariableError},
+        owned::{GrowthPolicy, OwnedBuf, VariableError},
         DefaultAlloc,
     };
 
@@ -494,47 +962,74 @@ mod owned_tests {
     }
 
     #[test]
-    fn test_remove_slice() {
-        let mut buf = OwnedBuf::<u32, DefaultAlloc>::new_in(16, DefaultAlloc).unwrap();
-        for i in 0..16 {
-            buf.try_init_next(i).unwrap();
+    fn test_growth_policies() {
+        fn sizes(policy: GrowthPolicy) -> Vec<usize> {
+            let mut buf = OwnedBuf::<u32, DefaultAlloc>::new_unallocated_in(DefaultAlloc)
+                .with_growth_policy(policy);
+            assert_eq!(buf.growth_policy(), policy);
+            let mut out = Vec::new();
+            for i in 0..10 {
+                buf.init_next_grow(i).unwrap();
+                if out.last() != Some(&buf.size()) {
+                    out.push(buf.size());
+                }
+            }
+            assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+            #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+            {
+                buf.drop_and_dealloc();
+            }
+            out
         }
-        // remove slice of length 3 at index 1
-        let maybe_slice = buf.remove_slice(3, 3);
-        assert!(maybe_slice.is_some());
-        let slice_res = maybe_slice.unwrap().unwrap();
-        assert_eq!(slice_res.initialized(), 3);
-        assert_eq!(slice_res.size(), 3);
-        assert_eq!(slice_res.init_buf(), &[3, 4, 5]);
-        // original buffer should have had 6 init, now 13 left
-        assert_eq!(buf.initialized(), 13);
+
+        assert_eq!(GrowthPolicy::default(), GrowthPolicy::Double);
+        assert_eq!(sizes(GrowthPolicy::Double), [1, 2, 4, 8, 16]);
+        assert_eq!(sizes(GrowthPolicy::OneAndHalf), [1, 2, 3, 4, 6, 9, 13]);
+        assert_eq!(sizes(GrowthPolicy::Chunk(4)), [4, 8, 12]);
+        assert_eq!(sizes(GrowthPolicy::Exact), (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_insert_grow_uses_policy() {
+        let mut buf = OwnedBuf::<u8, DefaultAlloc>::new_in(2, DefaultAlloc).unwrap();
+        buf.set_growth_policy(GrowthPolicy::Chunk(6));
+        buf.try_init_next(1).unwrap();
+        buf.try_init_next(3).unwrap();
+
+        assert!(buf.try_insert_grow(1, 2).is_ok());
+        assert_eq!(buf.size(), 8);
+        assert_eq!(buf.init_buf(), &[1, 2, 3]);
+
+        let mut tail = OwnedBuf::<u8, DefaultAlloc>::new_in(6, DefaultAlloc).unwrap();
+        for i in 4..10 {
+            tail.try_init_next(i).unwrap();
+        }
+        assert!(buf.try_insert_slice_grow(3, tail).is_ok());
+        // 8 + 6 = 14 is enough for the 9 elements
+        assert_eq!(buf.size(), 14);
+        assert_eq!(buf.init_buf(), &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
 
         #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
         {
             buf.drop_and_dealloc();
-            slice_res.drop_and_dealloc();
         }
     }
 
+    #[cfg(feature = "jemalloc")]
     #[test]
-    fn test_debug_and_display_errors() {
-        // Debug impl for OwnedBuf
-        let buf = OwnedBuf::<u8, DefaultAlloc>::new_unallocated_in(DefaultAlloc);
-        let s = format!("{buf:?}");
-        assert!(s.contains("OwnedBuf"));
-
-        // VariableError debug and display
-        let soft: VariableError<&str, &str> = VariableError::Soft("oops");
-        let hard: VariableError<&str, &str> = VariableError::Hard("boom");
-        let ds = format!("{soft:?}");
-        let dh = format!("{hard:?}");
-        assert!(ds.contains("Soft"));
-        assert!(dh.contains("Hard"));
-
-        let ls = format!("{soft}");
-        let lh = format!("{hard}");
-        assert!(ls.contains("oops"));
-        assert!(lh.contains("boom"));
+    fn test_growth_adopts_usable_size() {
+        use memapi::{jemalloc::Jemalloc, Alloc};
+
+        let mut buf = OwnedBuf::<u8, Jemalloc>::new_unallocated_in(Jemalloc)
+            .with_growth_policy(GrowthPolicy::Exact);
+        buf.init_next_grow(1).unwrap();
+        // jemalloc's smallest size class is larger than a single byte
+        let usable = unsafe {
+            Jemalloc.usable_size(buf.as_nonnull().cast(), core::alloc::Layout::new::<u8>())
+        };
+        assert!(usable > 1);
+        assert_eq!(buf.size(), usable);
 
         #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
         {
@@ -542,100 +1037,1988 @@ mod owned_tests {
         }
     }
 
-    // #[test]
-    // fn test_zst() {
-    //     struct Zst;
-    //
-    //     let mut buf = OwnedBuf::<Zst>::new(8);
-    // }
-}
+    #[test]
+    fn test_owned_into_iter() {
+        let mut buf = OwnedBuf::<u32, DefaultAlloc>::new_in(6, DefaultAlloc).unwrap();
+        for i in 0..5 {
+            buf.try_init_next(i).unwrap();
+        }
 
-#[cfg(all(feature = "jemalloc", not(miri)))]
-mod jemalloc_tests {
-    use core::{alloc::Layout, slice};
-    use memapi::{ffi::jem::usable_size, jemalloc::Jemalloc, type_props::SizedProps, Alloc};
+        let mut iter = buf.into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.as_slice(), &[1, 2, 3]);
+        iter.as_mut_slice()[0] = 10;
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<Vec<_>>(), [10, 2, 3]);
+
+        let empty = OwnedBuf::<u32, DefaultAlloc>::new_unallocated_in(DefaultAlloc);
+        assert_eq!(empty.into_iter().next(), None);
+    }
 
     #[test]
-    fn alloc_and_dealloc_basic() {
-        let alloc = Jemalloc;
-        let layout = Layout::from_size_align(64, 8).unwrap();
+    fn test_owned_into_iter_drops_remainder() {
+        use std::rc::Rc;
 
-        unsafe {
-            let ptr = alloc.alloc(layout).unwrap();
-            // we can write into the block
-            ptr.write_bytes(0xAB, layout.size());
-            alloc.dealloc(ptr, layout);
+        let counter = Rc::new(());
+        let mut buf = OwnedBuf::<Rc<()>, DefaultAlloc>::new_in(4, DefaultAlloc).unwrap();
+        for _ in 0..4 {
+            buf.try_init_next(Rc::clone(&counter)).unwrap();
         }
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        let mut iter = buf.into_iter();
+        let first = iter.next().unwrap();
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counter), 2);
+        drop(first);
+        assert_eq!(Rc::strong_count(&counter), 1);
     }
 
     #[test]
-    fn alloc_zeroed_is_really_zeroed() {
-        let alloc = Jemalloc;
-        let size = 128;
-        let layout = Layout::from_size_align(size, 8).unwrap();
+    fn test_owned_into_iter_zst() {
+        let mut buf = OwnedBuf::<(), DefaultAlloc>::new_unallocated_in(DefaultAlloc);
+        for _ in 0..3 {
+            buf.init_next_grow(()).unwrap();
+        }
+        let mut iter = buf.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(()));
+        assert_eq!(iter.count(), 2);
+    }
 
-        unsafe {
-            let ptr = alloc.alloc_zeroed(layout).unwrap();
-            // treat as byte slice
-            let buf = slice::from_raw_parts(ptr.as_ptr(), size);
-            assert!(
-                buf.iter().all(|&b| b == 0),
-                "alloc_zeroed must produce all-zero bytes"
-            );
-            alloc.dealloc(ptr, layout);
+    fn owned_from(vals: &[u32]) -> OwnedBuf<u32, DefaultAlloc> {
+        let mut buf = OwnedBuf::<u32, DefaultAlloc>::new_in(vals.len() + 2, DefaultAlloc).unwrap();
+        for &v in vals {
+            buf.try_init_next(v).unwrap();
         }
+        buf
     }
 
     #[test]
-    fn usable_size_at_least_requested() {
-        let alloc = Jemalloc;
-        let size = 100;
-        let layout = Layout::from_size_align(size, 16).unwrap();
+    fn test_drain() {
+        let mut buf = owned_from(&[0, 1, 2, 3, 4, 5]);
+        let size = buf.size();
+        assert!(buf.drain(2..7).is_none());
 
-        unsafe {
-            let ptr = alloc.alloc(layout).unwrap();
-            let usable = usable_size(ptr.as_ptr());
-            assert!(
-                usable >= size,
-                "usable_size {} should be >= requested {}",
-                usable,
-                size
-            );
-            alloc.dealloc(ptr, layout);
+        let drained = buf.drain(1..4).unwrap().collect::<Vec<_>>();
+        assert_eq!(drained, [1, 2, 3]);
+        assert_eq!(buf.init_buf(), &[0, 4, 5]);
+        assert_eq!(buf.size(), size);
+
+        // partially consumed drains still close the gap
+        let mut drain = buf.drain(..2).unwrap();
+        assert_eq!(drain.next_back(), Some(4));
+        assert_eq!(drain.as_slice(), &[0]);
+        drop(drain);
+        assert_eq!(buf.init_buf(), &[5]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
         }
     }
 
     #[test]
-    #[allow(clippy::cast_possible_truncation)]
-    fn realloc_preserves_initial_contents() {
-        let alloc = Jemalloc;
-        let old_count = 4;
-        let old_layout = Layout::array::<u32>(old_count).unwrap();
+    fn test_drain_drops_unyielded() {
+        use std::rc::Rc;
 
-        unsafe {
-            // allocate and write a pattern
-            let ptr = alloc.alloc(old_layout).unwrap().cast();
-            for i in 0..old_count {
-                ptr.add(i).write(i as u32 + 1);
-            }
+        let counter = Rc::new(());
+        let mut buf = OwnedBuf::<Rc<()>, DefaultAlloc>::new_in(4, DefaultAlloc).unwrap();
+        for _ in 0..4 {
+            buf.try_init_next(Rc::clone(&counter)).unwrap();
+        }
+        drop(buf.drain(1..=2).unwrap());
+        assert_eq!(Rc::strong_count(&counter), 3);
+        assert_eq!(buf.initialized(), 2);
 
-            // grow to twice as many elements
-            let new_size = old_count * 2 * u32::SZ;
-            let new_layout = Layout::from_size_align_unchecked(new_size, u32::ALIGN);
-            let new_ptr = alloc
-                .realloc(ptr.cast(), old_layout, new_layout)
-                .unwrap()
-                .cast();
+        buf.clear();
+        assert_eq!(Rc::strong_count(&counter), 1);
 
-            // original data should be intact
-            for i in 0..old_count {
-                let v: u32 = new_ptr.add(i).read();
-                assert_eq!(v, i as u32 + 1);
-            }
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+    }
 
-            // cleanup
+    #[test]
+    fn test_retain_and_dedup() {
+        let mut buf = owned_from(&[1, 2, 3, 4, 5, 6]);
+        let size = buf.size();
+        buf.retain(|&v| v % 2 == 0);
+        assert_eq!(buf.init_buf(), &[2, 4, 6]);
+        buf.retain_mut(|v| {
+            *v *= 10;
+            *v != 40
+        });
+        assert_eq!(buf.init_buf(), &[20, 60]);
+        assert_eq!(buf.size(), size);
+
+        let mut buf2 = owned_from(&[1, 1, 2, 3, 3, 3, 1]);
+        buf2.dedup();
+        assert_eq!(buf2.init_buf(), &[1, 2, 3, 1]);
+        buf2.dedup_by_key(|v| *v / 2);
+        assert_eq!(buf2.init_buf(), &[1, 2, 1]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+            buf2.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut buf = owned_from(&[0, 1, 2, 3]);
+
+        // shorter replacement
+        let removed = buf.splice(1..3, [9]).unwrap().collect::<Vec<_>>();
+        assert_eq!(removed, [1, 2]);
+        assert_eq!(buf.init_buf(), &[0, 9, 3]);
+
+        // longer replacement with an exact size hint
+        buf.splice(1..2, [7, 8, 9, 10]).unwrap().finish().unwrap();
+        assert_eq!(buf.init_buf(), &[0, 7, 8, 9, 10, 3]);
+
+        // longer replacement without a useful size hint
+        drop(buf.splice(..1, (0..5).filter(|_| true)).unwrap());
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4, 7, 8, 9, 10, 3]);
+
+        // pure insertion at the end
+        buf.splice(10.., [11]).unwrap().finish().unwrap();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4, 7, 8, 9, 10, 3, 11]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut buf = OwnedBuf::<u32, DefaultAlloc>::new_unallocated_in(DefaultAlloc);
+        buf.extend_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(buf.init_buf(), &[1, 2, 3]);
+
+        buf.try_extend(4..6).unwrap();
+        buf.extend([6, 7]);
+        buf.extend(&[8, 9]);
+        buf.extend((10..12).filter(|_| true));
+        assert_eq!(buf.init_buf(), &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        buf.extend_from_copy_slice(&[12, 13]).unwrap();
+        buf.extend_from_copy_slice(&[]).unwrap();
+        assert_eq!(buf.init_buf(), &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]);
+
+        let mut strings = OwnedBuf::<String, DefaultAlloc>::new_unallocated_in(DefaultAlloc);
+        strings
+            .extend_from_slice(&[String::from("a"), String::from("b")])
+            .unwrap();
+        assert_eq!(strings.init_buf(), &["a", "b"]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+            strings.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_from_iter_and_clone_from() {
+        let buf: OwnedBuf<u32, DefaultAlloc> = (0..5).collect();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4]);
+
+        let mut shorter: OwnedBuf<u32, DefaultAlloc> = (10..12).collect();
+        shorter.clone_from(&buf);
+        assert_eq!(shorter.init_buf(), buf.init_buf());
+
+        let mut longer: OwnedBuf<u32, DefaultAlloc> = (10..20).collect();
+        longer.clone_from(&buf);
+        assert_eq!(longer.init_buf(), buf.init_buf());
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+            shorter.drop_and_dealloc();
+            longer.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_insert_from_slice() {
+        let mut buf = owned_from(&[0, 1, 5]);
+        assert_eq!(buf.try_insert_from_slice(4, &[9]), Err(&[9][..]));
+        assert_eq!(
+            buf.try_insert_from_slice(2, &[2, 3, 4]),
+            Err(&[2, 3, 4][..])
+        );
+
+        buf.try_insert_from_slice(2, &[2, 3]).unwrap();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 5]);
+
+        assert!(matches!(
+            buf.try_insert_from_slice_grow(6, &[9]),
+            Err(VariableError::Soft(_))
+        ));
+        buf.try_insert_from_slice_grow(4, &[4, 4, 4]).unwrap();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4, 4, 4, 5]);
+
+        buf.replace_from_slice(5, &[7, 8]).unwrap();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4, 7, 8, 5]);
+        buf.replace_from_slice(6, &[6, 7, 8, 9]).unwrap();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4, 7, 6, 7, 8, 9]);
+
+        assert!(matches!(
+            buf.try_insert_iter_grow(11, 0..1),
+            Err(VariableError::Soft(_))
+        ));
+        buf.try_insert_iter_grow(0, [10, 11]).unwrap();
+        assert_eq!(buf.init_buf(), &[10, 11, 0, 1, 2, 3, 4, 7, 6, 7, 8, 9]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_insert_from_slice_clone_panic() {
+        use std::{
+            panic::{catch_unwind, AssertUnwindSafe},
+            rc::Rc,
+        };
+
+        #[derive(Debug)]
+        struct Bomb(Rc<()>, bool);
+        impl Clone for Bomb {
+            fn clone(&self) -> Bomb {
+                assert!(!self.1, "boom");
+                Bomb(Rc::clone(&self.0), false)
+            }
+        }
+
+        let counter = Rc::new(());
+        let mut buf = OwnedBuf::<Bomb, DefaultAlloc>::new_in(8, DefaultAlloc).unwrap();
+        for _ in 0..3 {
+            buf.try_init_next(Bomb(Rc::clone(&counter), false)).unwrap();
+        }
+        let src = [
+            Bomb(Rc::clone(&counter), false),
+            Bomb(Rc::clone(&counter), true),
+            Bomb(Rc::clone(&counter), false),
+        ];
+
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            let _ = buf.try_insert_from_slice(1, &src);
+        }));
+        assert!(res.is_err());
+        // the first clone was kept and the tail was shifted back
+        assert_eq!(buf.initialized(), 4);
+        assert_eq!(Rc::strong_count(&counter), 8);
+
+        buf.clear();
+        drop(src);
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_clear_drop_panic() {
+        use std::{
+            cell::Cell,
+            panic::{catch_unwind, AssertUnwindSafe},
+            rc::Rc,
+        };
+
+        #[derive(Debug)]
+        struct Bomb(Rc<Cell<usize>>, bool);
+        impl Drop for Bomb {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+                assert!(!self.1, "boom");
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        let mut buf = OwnedBuf::<Bomb, DefaultAlloc>::new_in(4, DefaultAlloc).unwrap();
+        for i in 0..3 {
+            buf.try_init_next(Bomb(Rc::clone(&drops), i == 1)).unwrap();
+        }
+
+        let res = catch_unwind(AssertUnwindSafe(|| buf.clear()));
+        assert!(res.is_err());
+        assert_eq!(buf.initialized(), 0);
+        assert_eq!(drops.get(), 3);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+        #[cfg(any(feature = "drop_for_owned", feature = "zero_drop_for_owned"))]
+        drop(buf);
+        // nothing was dropped twice
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_insert_shifts_overlapping() {
+        let mut buf = owned_from(&[1, 2, 3, 4]);
+        buf.try_insert(0, 0).unwrap();
+        assert_eq!(buf.init_buf(), &[0, 1, 2, 3, 4]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_into_chunks_and_flattened() {
+        let mut buf = OwnedBuf::<u16, DefaultAlloc>::new_in(7, DefaultAlloc).unwrap();
+        buf.try_extend(0..7).unwrap();
+
+        let buf = match buf.into_chunks::<0>() {
+            Err(VariableError::Soft(buf)) => buf,
+            _ => panic!("zero-sized chunks should be rejected"),
+        };
+        let chunks = buf.into_chunks::<3>().unwrap();
+        assert_eq!(chunks.initialized(), 2);
+        assert_eq!(chunks.size(), 2);
+        assert_eq!(chunks.init_buf(), &[[0, 1, 2], [3, 4, 5]]);
+
+        let mut flat = chunks.into_flattened();
+        assert_eq!(flat.initialized(), 6);
+        assert_eq!(flat.size(), 6);
+        assert_eq!(flat.init_buf(), &[0, 1, 2, 3, 4, 5]);
+        flat.init_next_grow(6).unwrap();
+        assert_eq!(flat.init_buf(), &[0, 1, 2, 3, 4, 5, 6]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            flat.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_into_array() {
+        let buf = owned_from(&[1, 2, 3]);
+        assert!(buf.size() > 3);
+
+        let buf = match buf.into_array::<4>() {
+            Err(VariableError::Soft(buf)) => buf,
+            _ => panic!("wrong length should be rejected"),
+        };
+        let arr = buf.into_array::<3>().unwrap();
+        assert_eq!(*arr, [1, 2, 3]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            arr.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_cast() {
+        // same alignment, reuses the allocation
+        let mut buf = OwnedBuf::<[u8; 4], DefaultAlloc>::new_in(3, DefaultAlloc).unwrap();
+        buf.try_extend([[1, 0, 0, 0], [2, 0, 0, 0]]).unwrap();
+        let ptr = buf.as_ptr().cast::<u8>();
+        let bytes = unsafe { buf.cast::<[u8; 2]>() }.unwrap();
+        assert_eq!(bytes.as_ptr().cast::<u8>(), ptr);
+        assert_eq!(bytes.initialized(), 4);
+        assert_eq!(bytes.size(), 6);
+        assert_eq!(bytes.init_buf(), &[[1, 0], [0, 0], [2, 0], [0, 0]]);
+
+        // different alignment, reallocates
+        let words = unsafe { bytes.cast::<u32>() }.unwrap();
+        assert_eq!(words.initialized(), 2);
+        assert_eq!(words.size(), 2);
+        assert_eq!(
+            words.init_buf(),
+            &[
+                u32::from_ne_bytes([1, 0, 0, 0]),
+                u32::from_ne_bytes([2, 0, 0, 0])
+            ]
+        );
+
+        // size mismatch
+        let mut odd = OwnedBuf::<u8, DefaultAlloc>::new_in(3, DefaultAlloc).unwrap();
+        odd.try_extend([1, 2, 3]).unwrap();
+        let odd = match unsafe { odd.cast::<u16>() } {
+            Err(VariableError::Soft(buf)) => buf,
+            _ => panic!("mismatched size should be rejected"),
+        };
+        assert_eq!(odd.init_buf(), &[1, 2, 3]);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            words.drop_and_dealloc();
+            odd.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    #[allow(clippy::assertions_on_constants)]
+    fn test_thread_safety() {
+        use memapi::owned::{Buf, HeapVal, OwnedIter};
+        use std::{rc::Rc, sync::Arc};
+
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+
+        assert_send::<OwnedBuf<u8, DefaultAlloc>>();
+        assert_sync::<OwnedBuf<u8, DefaultAlloc>>();
+        assert_send::<OwnedIter<u8, DefaultAlloc>>();
+        assert_sync::<OwnedIter<u8, DefaultAlloc>>();
+        assert_send::<HeapVal<u8, DefaultAlloc>>();
+        assert_sync::<HeapVal<u8, DefaultAlloc>>();
+        assert_send::<HeapVal<[u8], DefaultAlloc>>();
+        assert_sync::<HeapVal<str, DefaultAlloc>>();
+        assert_send::<Buf<'static, u8>>();
+        assert_sync::<Buf<'static, u8>>();
+        assert_send::<OwnedBuf<Arc<u8>, DefaultAlloc>>();
+
+        // `Rc` is neither, so this shouldn't be either.
+        trait NotSend {
+            const SEND: bool = false;
+        }
+        impl<T> NotSend for T {}
+        struct IsSend<T>(T);
+        #[allow(dead_code)]
+        impl<T: Send> IsSend<T> {
+            const SEND: bool = true;
+        }
+        assert!(!<IsSend<OwnedBuf<Rc<u8>, DefaultAlloc>>>::SEND);
+        assert!(!<IsSend<HeapVal<Rc<u8>, DefaultAlloc>>>::SEND);
+        assert!(<IsSend<OwnedBuf<u8, DefaultAlloc>>>::SEND);
+
+        let buf = owned_from(&[1, 2, 3]);
+        let sum = std::thread::spawn(move || buf.into_iter().sum::<u32>())
+            .join()
+            .unwrap();
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn test_remove_slice() {
+        let mut buf = OwnedBuf::<u32, DefaultAlloc>::new_in(16, DefaultAlloc).unwrap();
+        for i in 0..16 {
+            buf.try_init_next(i).unwrap();
+        }
+        // remove slice of length 3 at index 1
+        let maybe_slice = buf.remove_slice(3, 3);
+        assert!(maybe_slice.is_some());
+        let slice_res = maybe_slice.unwrap().unwrap();
+        assert_eq!(slice_res.initialized(), 3);
+        assert_eq!(slice_res.size(), 3);
+        assert_eq!(slice_res.init_buf(), &[3, 4, 5]);
+        // original buffer should have had 6 init, now 13 left
+        assert_eq!(buf.initialized(), 13);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+            slice_res.drop_and_dealloc();
+        }
+    }
+
+    #[test]
+    fn test_debug_and_display_errors() {
+        // Debug impl for OwnedBuf
+        let buf = OwnedBuf::<u8, DefaultAlloc>::new_unallocated_in(DefaultAlloc);
+        let s = format!("{buf:?}");
+        assert!(s.contains("OwnedBuf"));
+
+        // VariableError debug and display
+        let soft: VariableError<&str, &str> = VariableError::Soft("oops");
+        let hard: VariableError<&str, &str> = VariableError::Hard("boom");
+        let ds = format!("{soft:?}");
+        let dh = format!("{hard:?}");
+        assert!(ds.contains("Soft"));
+        assert!(dh.contains("Hard"));
+
+        let ls = format!("{soft}");
+        let lh = format!("{hard}");
+        assert!(ls.contains("oops"));
+        assert!(lh.contains("boom"));
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+        }
+    }
+
+    // #[test]
+    // fn test_zst() {
+    //     struct Zst;
+    //
+    //     let mut buf = OwnedBuf::<Zst>::new(8);
+    // }
+}
+
+#[cfg(feature = "bump")]
+mod bump_tests {
+    use core::alloc::Layout;
+    use memapi::{bump::BumpAlloc, error::AllocError, Alloc, DefaultAlloc};
+
+    #[test]
+    fn allocations_are_aligned_and_distinct() {
+        let bump = BumpAlloc::new();
+        let a = bump.alloc(Layout::new::<u8>()).unwrap();
+        let b = bump.alloc(Layout::new::<u64>()).unwrap();
+        assert_eq!(b.as_ptr() as usize % 8, 0);
+        assert!(b.as_ptr() as usize > a.as_ptr() as usize);
+        assert!(matches!(
+            bump.alloc(Layout::from_size_align(0, 1).unwrap()),
+            Err(AllocError::ZeroSizedLayout(_))
+        ));
+    }
+
+    #[test]
+    fn dealloc_rolls_back_last() {
+        let bump = BumpAlloc::new();
+        let layout = Layout::new::<[u8; 16]>();
+        let a = bump.alloc(layout).unwrap();
+        let b = bump.alloc(layout).unwrap();
+        unsafe {
+            // not the most recent, so this is a no-op
+            bump.dealloc(a, layout);
+            bump.dealloc(b, layout);
+        }
+        assert_eq!(bump.alloc(layout).unwrap(), b);
+    }
+
+    #[test]
+    fn grows_last_in_place() {
+        let bump = BumpAlloc::new();
+        let old = Layout::from_size_align(8, 8).unwrap();
+        let new = Layout::from_size_align(64, 8).unwrap();
+        let first = bump.alloc(old).unwrap();
+        unsafe {
+            first.as_ptr().write_bytes(7, 8);
+            let grown = bump.grow_zeroed(first, old, new).unwrap();
+            assert_eq!(grown, first);
+            assert_eq!(*grown.as_ptr(), 7);
+            assert_eq!(*grown.as_ptr().add(63), 0);
+
+            let second = bump.alloc(old).unwrap();
+            // the first block isn't the most recent anymore, so it has to move
+            let moved = bump
+                .grow(grown, new, Layout::from_size_align(128, 8).unwrap())
+                .unwrap();
+            assert_ne!(moved, grown);
+            assert_ne!(moved, second);
+            assert_eq!(*moved.as_ptr(), 7);
+        }
+    }
+
+    #[test]
+    fn spills_into_new_chunks_and_resets() {
+        let mut bump = BumpAlloc::with_chunk_size_in(128, DefaultAlloc);
+        assert_eq!(bump.allocated_bytes(), 0);
+        let layout = Layout::new::<[u8; 64]>();
+        for _ in 0..4 {
+            bump.alloc(layout).unwrap();
+        }
+        // oversized requests get a chunk of their own
+        bump.alloc(Layout::new::<[u8; 1024]>()).unwrap();
+        let before = bump.allocated_bytes();
+        assert!(before > 1024 + 4 * 64);
+
+        bump.reset();
+        assert!(bump.allocated_bytes() < before);
+        bump.alloc(layout).unwrap();
+    }
+
+    #[test]
+    fn checkpoint_and_rewind() {
+        let mut bump = BumpAlloc::with_chunk_size_in(128, DefaultAlloc);
+        let layout = Layout::new::<[u8; 48]>();
+        let empty = bump.checkpoint();
+        let a = bump.alloc(layout).unwrap();
+        let cp = bump.checkpoint();
+        let b = bump.alloc(layout).unwrap();
+        for _ in 0..8 {
+            bump.alloc(layout).unwrap();
+        }
+        let later = bump.checkpoint();
+
+        bump.rewind(cp).unwrap();
+        assert_eq!(bump.alloc(layout).unwrap(), b);
+        assert_ne!(bump.alloc(layout).unwrap(), a);
+
+        // rewinding past a checkpoint invalidates it
+        assert!(matches!(bump.rewind(later), Err(AllocError::Other(_))));
+
+        bump.rewind(empty).unwrap();
+        assert_eq!(bump.allocated_bytes(), 0);
+        assert!(matches!(bump.rewind(cp), Err(AllocError::Other(_))));
+    }
+
+    #[test]
+    fn owns_only_its_chunks() {
+        use memapi::Owns;
+
+        let mut bump = BumpAlloc::with_chunk_size_in(128, DefaultAlloc);
+        let layout = Layout::new::<[u8; 48]>();
+        let empty = bump.checkpoint();
+        let a = bump.alloc(layout).unwrap();
+        let b = bump.alloc(layout).unwrap();
+        let foreign = DefaultAlloc.alloc(layout).unwrap();
+        assert!(bump.owns(a, layout));
+        assert!(bump.owns(b, layout));
+        assert!(!bump.owns(foreign, layout));
+        let precise = <BumpAlloc as Owns>::PRECISE;
+        assert!(precise);
+
+        // every chunk is returned, so nothing is owned anymore
+        bump.rewind(empty).unwrap();
+        assert!(!bump.owns(b, layout));
+        unsafe {
+            DefaultAlloc.dealloc(foreign, layout);
+        }
+    }
+
+    #[cfg(feature = "owned")]
+    #[test]
+    fn backs_owned_types() {
+        use memapi::owned::{GrowthPolicy, HeapVal, OwnedBuf};
+
+        let bump = BumpAlloc::new();
+        let val = HeapVal::new_in(5u64, &bump).unwrap();
+        let mut buf =
+            OwnedBuf::<u32, _>::new_unallocated_in(&bump).with_growth_policy(GrowthPolicy::Exact);
+        buf.init_next_grow(0).unwrap();
+        let start = buf.as_ptr();
+        for i in 1..100 {
+            buf.init_next_grow(i).unwrap();
+        }
+        // the buffer is the most recent allocation, so it grew in place every time
+        assert_eq!(buf.as_ptr(), start);
+        assert_eq!(buf.init_buf().iter().sum::<u32>(), 4950);
+        assert_eq!(*val, 5);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            buf.drop_and_dealloc();
+            val.drop_and_dealloc();
+        }
+    }
+}
+
+#[cfg(feature = "pool")]
+mod pool_tests {
+    use core::alloc::Layout;
+    use memapi::{error::AllocError, pool::Pool, Alloc, DefaultAlloc};
+
+    #[test]
+    fn reuses_freed_slots() {
+        let pool = Pool::new(Layout::new::<[u64; 4]>()).unwrap();
+        let layout = Layout::new::<[u64; 4]>();
+        let a = pool.alloc(layout).unwrap();
+        let b = pool.alloc(Layout::new::<u8>()).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.as_ptr() as usize % 8, 0);
+        assert_eq!(pool.in_use(), 2);
+        unsafe {
+            pool.dealloc(a, layout);
+        }
+        assert_eq!(pool.in_use(), 1);
+        assert_eq!(pool.alloc(layout).unwrap(), a);
+    }
+
+    #[test]
+    fn rejects_layouts_that_dont_fit() {
+        let pool = Pool::new(Layout::new::<[u32; 2]>()).unwrap();
+        assert!(pool.fits(Layout::new::<u64>()));
+        assert!(matches!(
+            pool.alloc(Layout::new::<[u8; 64]>()),
+            Err(AllocError::AllocFailed(_))
+        ));
+        assert!(matches!(
+            pool.alloc(Layout::from_size_align(8, 64).unwrap()),
+            Err(AllocError::AllocFailed(_))
+        ));
+        assert!(matches!(
+            pool.alloc(Layout::from_size_align(0, 1).unwrap()),
+            Err(AllocError::ZeroSizedLayout(_))
+        ));
+        assert_eq!(pool.slabs(), 0);
+    }
+
+    #[test]
+    fn resizes_within_slot_in_place() {
+        let pool = Pool::new(Layout::new::<[u8; 32]>()).unwrap();
+        let old = Layout::new::<[u8; 8]>();
+        let ptr = pool.alloc(old).unwrap();
+        unsafe {
+            ptr.as_ptr().write_bytes(3, 8);
+            assert_eq!(pool.usable_size(ptr, old), 32);
+            let grown = pool
+                .grow_zeroed(ptr, old, Layout::new::<[u8; 32]>())
+                .unwrap();
+            assert_eq!(grown, ptr);
+            assert_eq!(*grown.as_ptr().add(7), 3);
+            assert_eq!(*grown.as_ptr().add(31), 0);
+            assert!(matches!(
+                pool.grow(grown, Layout::new::<[u8; 32]>(), Layout::new::<[u8; 64]>()),
+                Err(AllocError::AllocFailed(_))
+            ));
+            let shrunk = pool.shrink(grown, Layout::new::<[u8; 32]>(), old).unwrap();
+            assert_eq!(shrunk, ptr);
+            pool.dealloc(shrunk, old);
+        }
+    }
+
+    #[test]
+    fn releases_empty_slabs() {
+        let layout = Layout::new::<u64>();
+        let pool = Pool::with_slots_in(layout, 4, DefaultAlloc).unwrap();
+        // the slab is rounded up to a power of two, which fits more than the requested slots.
+        let per = pool.slots_per_slab();
+        assert!(per > 4);
+        let ptrs = (0..2 * per + 1)
+            .map(|_| pool.alloc(layout).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(pool.slabs(), 3);
+        assert_eq!(pool.release_empty(), 0);
+
+        // free everything but one slot in the first slab
+        for &p in &ptrs[1..] {
+            unsafe {
+                pool.dealloc(p, layout);
+            }
+        }
+        assert_eq!(pool.release_empty(), 2);
+        assert_eq!(pool.slabs(), 1);
+        assert_eq!(pool.in_use(), 1);
+
+        // the remaining slab's free slots are still usable
+        let again = (0..per - 1)
+            .map(|_| pool.alloc(layout).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(pool.slabs(), 1);
+        for p in again.into_iter().chain(Some(ptrs[0])) {
+            unsafe {
+                pool.dealloc(p, layout);
+            }
+        }
+        assert_eq!(pool.release_empty(), 1);
+        assert_eq!(pool.slabs(), 0);
+    }
+
+    #[test]
+    fn owns_only_its_slabs() {
+        use memapi::Owns;
+
+        let layout = Layout::new::<u64>();
+        let pool = Pool::with_slots_in(layout, 2, DefaultAlloc).unwrap();
+        let a = pool.alloc(layout).unwrap();
+        let foreign = DefaultAlloc.alloc(layout).unwrap();
+        assert!(pool.owns(a, layout));
+        assert!(!pool.owns(foreign, layout));
+        unsafe {
+            pool.dealloc(a, layout);
+            DefaultAlloc.dealloc(foreign, layout);
+        }
+        pool.release_empty();
+        assert!(!pool.owns(a, layout));
+    }
+
+    #[cfg(feature = "limited")]
+    #[test]
+    fn propagates_backing_errors() {
+        use memapi::limited::Limited;
+
+        let layout = Layout::new::<u64>();
+        let pool = Pool::new_in(layout, Limited::new(64)).unwrap();
+        assert!(matches!(
+            pool.alloc(layout),
+            Err(AllocError::LimitExceeded(64, 0, _))
+        ));
+    }
+
+    #[cfg(feature = "alloc_ext")]
+    #[test]
+    fn works_with_alloc_write() {
+        use memapi::AllocExt;
+
+        let pool = Pool::for_type_in::<(u32, u64)>(DefaultAlloc).unwrap();
+        let ptr = pool.alloc_write((1u32, 2u64)).unwrap();
+        unsafe {
+            assert_eq!(*ptr.as_ptr(), (1, 2));
+            pool.drop_and_dealloc(ptr);
+        }
+        assert_eq!(pool.in_use(), 0);
+    }
+
+    #[cfg(feature = "owned")]
+    #[test]
+    fn backs_heap_vals() {
+        use memapi::owned::HeapVal;
+
+        let pool = Pool::for_type_in::<[u16; 3]>(DefaultAlloc).unwrap();
+        let a = HeapVal::new_in([1u16, 2, 3], &pool).unwrap();
+        let b = HeapVal::new_in([4u16, 5, 6], &pool).unwrap();
+        assert_eq!(pool.in_use(), 2);
+        assert_eq!(a[2] + b[0], 7);
+
+        #[cfg(not(any(feature = "drop_for_owned", feature = "zero_drop_for_owned")))]
+        {
+            a.drop_and_dealloc();
+            b.drop_and_dealloc();
+        }
+        #[cfg(any(feature = "drop_for_owned", feature = "zero_drop_for_owned"))]
+        {
+            drop(a);
+            drop(b);
+        }
+        assert_eq!(pool.in_use(), 0);
+    }
+}
+
+#[cfg(all(feature = "fallback", feature = "pool"))]
+mod fallback_tests {
+    use core::alloc::Layout;
+    use memapi::{fallback::Fallback, pool::Pool, Alloc, DefaultAlloc, Owns};
+
+    fn small_first() -> Fallback<Pool, DefaultAlloc> {
+        Fallback::new(Pool::new(Layout::new::<[u64; 2]>()).unwrap(), DefaultAlloc)
+    }
+
+    #[test]
+    fn routes_by_owner() {
+        let a = small_first();
+        let small = Layout::new::<u64>();
+        let big = Layout::new::<[u64; 8]>();
+        let s = a.alloc(small).unwrap();
+        let b = a.alloc(big).unwrap();
+        assert!(a.primary().owns(s, small));
+        assert!(!a.primary().owns(b, big));
+        assert_eq!(a.primary().in_use(), 1);
+        unsafe {
+            a.dealloc(b, big);
+            a.dealloc(s, small);
+        }
+        assert_eq!(a.primary().in_use(), 0);
+    }
+
+    #[test]
+    fn grow_moves_to_secondary() {
+        let a = small_first();
+        let old = Layout::new::<[u8; 16]>();
+        let new = Layout::new::<[u8; 64]>();
+        unsafe {
+            let ptr = a.alloc(old).unwrap();
+            for i in 0..16 {
+                *ptr.as_ptr().add(i) = i as u8;
+            }
+            let moved = a.grow_zeroed(ptr, old, new).unwrap();
+            assert!(!a.primary().owns(moved, new));
+            assert_eq!(a.primary().in_use(), 0);
+            for i in 0..16 {
+                assert_eq!(*moved.as_ptr().add(i), i as u8);
+            }
+            assert_eq!(*moved.as_ptr().add(63), 0);
+
+            // blocks owned by the secondary allocator stay there
+            let shrunk = a.realloc(moved, new, old).unwrap();
+            assert!(!a.primary().owns(shrunk, old));
+            assert_eq!(*shrunk.as_ptr().add(15), 15);
+            a.dealloc(shrunk, old);
+        }
+    }
+
+    #[test]
+    fn grows_within_primary_in_place() {
+        let a = small_first();
+        let old = Layout::new::<u32>();
+        let new = Layout::new::<[u32; 4]>();
+        unsafe {
+            let ptr = a.alloc(old).unwrap();
+            assert_eq!(a.grow(ptr, old, new).unwrap(), ptr);
+            assert_eq!(a.usable_size(ptr, new), 16);
+            a.dealloc(ptr, new);
+        }
+        assert_eq!(a.primary().in_use(), 0);
+    }
+
+    #[cfg(feature = "limited")]
+    #[test]
+    fn limit_exceeded_moves_to_secondary() {
+        use memapi::limited::Limited;
+
+        let pool = Pool::new(Layout::new::<[u64; 2]>()).unwrap();
+        let a = Fallback::new(Limited::new_in(12, pool), DefaultAlloc);
+        let old = Layout::new::<u64>();
+        let new = Layout::new::<[u64; 2]>();
+        unsafe {
+            let ptr = a.alloc(old).unwrap();
+            ptr.as_ptr().write_bytes(0x3C, 8);
+            let moved = a.grow(ptr, old, new).unwrap();
+            assert!(!a.primary().owns(moved, new));
+            assert_eq!(a.primary().inner().in_use(), 0);
+            assert_eq!(a.primary().budget().used(), 0);
+            assert_eq!(*moved.as_ptr().add(7), 0x3C);
+            a.dealloc(moved, new);
+        }
+    }
+}
+
+#[cfg(all(feature = "segregate", feature = "stats", feature = "std"))]
+mod segregate_tests {
+    use core::{
+        alloc::Layout,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use memapi::{error::AllocError, segregate::Segregate, stats::Stats, Alloc, DefaultAlloc};
+
+    type Split<'l> =
+        Segregate<16, Stats<DefaultAlloc, &'l AtomicUsize>, Stats<DefaultAlloc, &'l AtomicUsize>>;
+
+    fn split<'l>(small: &'l AtomicUsize, large: &'l AtomicUsize) -> Split<'l> {
+        Segregate::new(Stats::new(small), Stats::new(large))
+    }
+
+    #[test]
+    fn routes_by_size() {
+        let (small, large) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let a = split(&small, &large);
+        let s = a.alloc(Layout::new::<[u8; 16]>()).unwrap();
+        let l = a.alloc(Layout::new::<[u8; 17]>()).unwrap();
+        assert_eq!(small.load(Ordering::SeqCst), 16);
+        assert_eq!(large.load(Ordering::SeqCst), 17);
+        unsafe {
+            a.dealloc(s, Layout::new::<[u8; 16]>());
+            a.dealloc(l, Layout::new::<[u8; 17]>());
+        }
+        assert_eq!(small.load(Ordering::SeqCst), 0);
+        assert_eq!(large.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn resizing_across_threshold_moves_data() {
+        let (small, large) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let a = split(&small, &large);
+        let old = Layout::new::<[u8; 8]>();
+        let new = Layout::new::<[u8; 64]>();
+        unsafe {
+            let ptr = a.alloc(old).unwrap();
+            for i in 0..8 {
+                *ptr.as_ptr().add(i) = i as u8 + 1;
+            }
+            let grown = a.grow_zeroed(ptr, old, new).unwrap();
+            assert_eq!(small.load(Ordering::SeqCst), 0);
+            assert_eq!(large.load(Ordering::SeqCst), 64);
+            assert_eq!(*grown.as_ptr().add(7), 8);
+            assert_eq!(*grown.as_ptr().add(63), 0);
+
+            let back = a.realloc(grown, new, old).unwrap();
+            assert_eq!(small.load(Ordering::SeqCst), 8);
+            assert_eq!(large.load(Ordering::SeqCst), 0);
+            assert_eq!(*back.as_ptr(), 1);
+
+            assert!(matches!(
+                a.shrink(back, old, new),
+                Err(AllocError::ShrinkBiggerNewLayout(8, 64))
+            ));
+            a.dealloc(back, old);
+        }
+    }
+
+    #[test]
+    fn usable_size_stays_below_threshold() {
+        let (small, large) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let a = split(&small, &large);
+        let layout = Layout::new::<[u8; 12]>();
+        let ptr = a.alloc(layout).unwrap();
+        unsafe {
+            assert!(a.usable_size(ptr, layout) <= 16);
+            a.dealloc(ptr, layout);
+        }
+    }
+}
+
+#[cfg(feature = "limited")]
+mod limited_tests {
+    use core::alloc::Layout;
+    use memapi::{
+        error::AllocError,
+        limited::{Budget, Limited},
+        Alloc, DefaultAlloc,
+    };
+
+    #[test]
+    fn fails_past_the_limit() {
+        let a = Limited::new(64);
+        let layout = Layout::new::<[u8; 48]>();
+        let ptr = a.alloc(layout).unwrap();
+        assert_eq!(a.budget().used(), 48);
+        assert_eq!(
+            a.alloc(Layout::new::<[u8; 32]>()),
+            Err(AllocError::LimitExceeded(64, 48, 32))
+        );
+        // failed allocations don't count
+        assert_eq!(a.budget().used(), 48);
+        unsafe {
+            a.dealloc(ptr, layout);
+        }
+        assert_eq!(a.budget().used(), 0);
+    }
+
+    #[test]
+    fn resizing_is_accounted() {
+        let a = Limited::new(100);
+        let small = Layout::new::<[u8; 40]>();
+        let big = Layout::new::<[u8; 80]>();
+        unsafe {
+            let ptr = a.alloc(small).unwrap();
+            let ptr = a.grow(ptr, small, big).unwrap();
+            assert_eq!(a.budget().used(), 80);
+            assert_eq!(
+                a.grow(ptr, big, Layout::new::<[u8; 120]>()),
+                Err(AllocError::LimitExceeded(100, 80, 40))
+            );
+            let ptr = a.realloc(ptr, big, small).unwrap();
+            assert_eq!(a.budget().used(), 40);
+            let ptr = a.realloc_zeroed(ptr, small, big).unwrap();
+            assert_eq!(a.budget().used(), 80);
+            let ptr = a.shrink(ptr, big, small).unwrap();
+            assert_eq!(a.budget().used(), 40);
+            a.dealloc(ptr, small);
+        }
+        assert_eq!(a.budget().used(), 0);
+    }
+
+    #[test]
+    fn limit_can_change_at_runtime() {
+        let a = Limited::new(16);
+        let layout = Layout::new::<[u8; 32]>();
+        assert!(matches!(
+            a.alloc(layout),
+            Err(AllocError::LimitExceeded(..))
+        ));
+        a.budget().set_limit(32);
+        let ptr = a.alloc(layout).unwrap();
+        assert_eq!(a.budget().remaining(), 0);
+
+        // lowering the limit below usage only blocks new allocations
+        a.budget().set_limit(8);
+        assert_eq!(
+            a.alloc(Layout::new::<u8>()),
+            Err(AllocError::LimitExceeded(8, 32, 1))
+        );
+        unsafe {
+            a.dealloc(ptr, layout);
+        }
+        a.alloc(Layout::new::<u8>()).unwrap();
+    }
+
+    #[test]
+    fn budgets_can_be_shared() {
+        let budget = Budget::new(64);
+        let a = Limited::with_budget_in(&budget, DefaultAlloc);
+        let b = Limited::with_budget_in(&budget, DefaultAlloc);
+        let layout = Layout::new::<[u8; 40]>();
+        let ptr = a.alloc(layout).unwrap();
+        assert!(matches!(
+            b.alloc(layout),
+            Err(AllocError::LimitExceeded(64, 40, 40))
+        ));
+        unsafe {
+            a.dealloc(ptr, layout);
+        }
+        let ptr = b.alloc(layout).unwrap();
+        assert_eq!(budget.used(), 40);
+        unsafe {
+            b.dealloc(ptr, layout);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn budgets_can_be_shared_across_threads() {
+        use std::sync::Arc;
+
+        let budget = Arc::new(Budget::new(1024));
+        let handles = (0..4)
+            .map(|_| {
+                let a = Limited::with_budget_in(budget.clone(), DefaultAlloc);
+                std::thread::spawn(move || {
+                    let layout = Layout::new::<[u8; 16]>();
+                    for _ in 0..1000 {
+                        let ptr = a.alloc(layout).unwrap();
+                        unsafe {
+                            a.dealloc(ptr, layout);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(budget.used(), 0);
+    }
+}
+
+#[cfg(feature = "failing")]
+mod failing_tests {
+    use core::alloc::Layout;
+    #[cfg(any(feature = "owned", feature = "alloc_slice"))]
+    use core::{cell::Cell, ptr::NonNull};
+    #[cfg(any(feature = "owned", feature = "alloc_slice"))]
+    use memapi::DefaultAlloc;
+    use memapi::{
+        error::AllocError,
+        failing::{FailOps, FailingAlloc},
+        Alloc,
+    };
+
+    /// Counts live blocks, panicking on more deallocations than allocations.
+    #[cfg(any(feature = "owned", feature = "alloc_slice"))]
+    #[derive(Default)]
+    struct Tracking {
+        live: Cell<usize>,
+    }
+
+    #[cfg(any(feature = "owned", feature = "alloc_slice"))]
+    impl Alloc for Tracking {
+        fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+            let ptr = DefaultAlloc.alloc(layout)?;
+            self.live.set(self.live.get() + 1);
+            Ok(ptr)
+        }
+
+        unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live
+                .set(self.live.get().checked_sub(1).expect("double free"));
+            DefaultAlloc.dealloc(ptr, layout);
+        }
+    }
+
+    fn outcomes<A: Alloc>(a: &A, n: usize) -> Vec<bool> {
+        let layout = Layout::new::<u64>();
+        (0..n)
+            .map(|_| match a.alloc(layout) {
+                Ok(ptr) => {
+                    unsafe {
+                        a.dealloc(ptr, layout);
+                    }
+                    true
+                }
+                Err(_) => false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fails_nth_and_every_nth_call() {
+        let a = FailingAlloc::new().fail_nth(2);
+        assert_eq!(outcomes(&a, 4), [true, false, true, true]);
+        assert_eq!((a.calls(), a.failures()), (4, 1));
+
+        a.reset_counts();
+        a.set_fail_nth(0);
+        a.set_fail_every(3);
+        assert_eq!(outcomes(&a, 6), [true, true, false, true, true, false]);
+    }
+
+    #[test]
+    fn fails_above_threshold() {
+        let a = FailingAlloc::new().fail_above(8);
+        let small = a.alloc(Layout::new::<u64>()).unwrap();
+        assert_eq!(
+            a.alloc(Layout::new::<[u64; 2]>()),
+            Err(AllocError::AllocFailed(Layout::new::<[u64; 2]>()))
+        );
+        unsafe {
+            assert!(a
+                .grow(small, Layout::new::<u64>(), Layout::new::<[u64; 2]>())
+                .is_err());
+            // the block is untouched by the failed grow
+            a.dealloc(small, Layout::new::<u64>());
+        }
+    }
+
+    #[test]
+    fn fails_only_selected_ops() {
+        let a = FailingAlloc::new().only(FailOps::GROW).fail_every(1);
+        let old = Layout::new::<[u8; 16]>();
+        let ptr = a.alloc(old).unwrap();
+        unsafe {
+            ptr.as_ptr().write_bytes(9, 16);
+            assert!(a.grow(ptr, old, Layout::new::<[u8; 32]>()).is_err());
+            assert!(a.realloc(ptr, old, Layout::new::<[u8; 32]>()).is_err());
+            let shrunk = a.realloc(ptr, old, Layout::new::<[u8; 8]>()).unwrap();
+            assert_eq!(*shrunk.as_ptr().add(7), 9);
+            a.dealloc(shrunk, Layout::new::<[u8; 8]>());
+        }
+        assert_eq!(a.calls(), 2);
+        assert!(FailOps::ALL.contains(FailOps::ALLOC | FailOps::SHRINK));
+    }
+
+    #[test]
+    fn random_failures_are_deterministic() {
+        let a = FailingAlloc::new().fail_fraction(1, 4, 42);
+        let b = FailingAlloc::new().fail_fraction(1, 4, 42);
+        let c = FailingAlloc::new().fail_fraction(1, 4, 7);
+        let (oa, ob, oc) = (outcomes(&a, 1000), outcomes(&b, 1000), outcomes(&c, 1000));
+        assert_eq!(oa, ob);
+        assert_ne!(oa, oc);
+        assert!((150..350).contains(&a.failures()));
+    }
+
+    #[cfg(feature = "owned")]
+    #[test]
+    fn owned_buf_survives_failures() {
+        use memapi::owned::{GrowthPolicy, OwnedBuf};
+        use std::rc::Rc;
+
+        let tracking = Tracking::default();
+        let a = FailingAlloc::new_in(&tracking).fail_every(3);
+        let counter = Rc::new(());
+        let mut buf = OwnedBuf::new_unallocated_in(&a).with_growth_policy(GrowthPolicy::Exact);
+        let mut pushed = 0;
+        for _ in 0..20 {
+            if buf.init_next_grow(counter.clone()).is_ok() {
+                pushed += 1;
+            }
+        }
+        assert!(a.failures() > 0);
+        assert_eq!(buf.initialized(), pushed);
+        // failed pushes dropped their value instead of leaking it
+        assert_eq!(Rc::strong_count(&counter), pushed + 1);
+
+        drop(buf.into_iter());
+        assert_eq!(Rc::strong_count(&counter), 1);
+        assert_eq!(tracking.live.get(), 0);
+    }
+
+    #[cfg(feature = "alloc_slice")]
+    #[test]
+    fn alloc_slice_survives_failures() {
+        use memapi::alloc_slice::AllocSlice;
+
+        let tracking = Tracking::default();
+        let a = FailingAlloc::new_in(&tracking).fail_nth(2);
+        unsafe {
+            let slice = a.alloc_slice_with::<u32, _>(4, |i| i as u32).unwrap();
+            assert!(a.grow_slice_with(slice, 8, |i| i as u32).is_err());
+            assert_eq!(slice.as_ref(), &[0, 1, 2, 3]);
+            let grown = a.grow_slice_with(slice, 8, |i| i as u32).unwrap();
+            assert_eq!(grown.as_ref(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+            a.dealloc_n(grown.cast::<u32>(), 8);
+        }
+        assert_eq!(tracking.live.get(), 0);
+    }
+}
+
+#[cfg(feature = "guarded")]
+mod guarded_tests {
+    use core::{alloc::Layout, cell::Cell, sync::atomic::AtomicUsize};
+    use memapi::{
+        error::{Violation, ViolationKind},
+        guarded::{Guarded, CANARY},
+        Alloc, DefaultAlloc,
+    };
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn intact_blocks_pass() {
+        let a = Guarded::new();
+        let layout = Layout::new::<[u8; 24]>();
+        let ptr = a.alloc(layout).unwrap();
+        unsafe {
+            ptr.as_ptr().write_bytes(0xFF, 24);
+            assert_eq!(*ptr.as_ptr().sub(1), CANARY);
+            assert_eq!(*ptr.as_ptr().add(24), CANARY);
+            assert!(a.check(ptr, layout));
+            a.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn detects_overflow_and_underflow() {
+        let a = Guarded::with_handler_in(AtomicUsize::new(0), DefaultAlloc);
+        let layout = Layout::new::<[u8; 8]>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            *ptr.as_ptr().add(8) = 0;
+            a.dealloc(ptr, layout);
+            assert_eq!(a.handler().load(Ordering::SeqCst), 1);
+
+            let ptr = a.alloc(layout).unwrap();
+            *ptr.as_ptr().sub(1) = 0;
+            *ptr.as_ptr().add(9) = 0;
+            assert!(!a.check(ptr, layout));
+            assert_eq!(a.handler().load(Ordering::SeqCst), 3);
+            a.dealloc(ptr, layout);
+            assert_eq!(a.handler().load(Ordering::SeqCst), 5);
+        }
+    }
+
+    #[test]
+    fn reports_kind_pointer_and_layout() {
+        let seen = Cell::new(None);
+        let a = Guarded::with_handler_in(|v: &Violation| seen.set(Some(*v)), DefaultAlloc);
+        let layout = Layout::new::<[u16; 4]>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            *ptr.as_ptr().sub(3) = 0;
+            a.dealloc(ptr, layout);
+            let v = seen.get().unwrap();
+            assert_eq!(v.kind, ViolationKind::Underflow);
+            assert_eq!((v.ptr, v.layout), (ptr, layout));
+        }
+    }
+
+    #[test]
+    fn honors_large_alignments() {
+        let a = Guarded::new();
+        for align in [1, 8, 32, 256, 4096] {
+            let layout = Layout::from_size_align(3, align).unwrap();
+            let ptr = a.alloc(layout).unwrap();
+            assert_eq!(ptr.as_ptr() as usize % align, 0);
+            unsafe {
+                ptr.as_ptr().write_bytes(1, 3);
+                a.dealloc(ptr, layout);
+            }
+        }
+    }
+
+    #[test]
+    fn resizing_keeps_data_and_guards() {
+        let a = Guarded::with_handler_in(AtomicUsize::new(0), DefaultAlloc);
+        let small = Layout::new::<[u8; 4]>();
+        let big = Layout::new::<[u8; 64]>();
+        unsafe {
+            let ptr = a.alloc(small).unwrap();
+            ptr.as_ptr().write_bytes(7, 4);
+            let ptr = a.grow_zeroed(ptr, small, big).unwrap();
+            assert_eq!(
+                core::slice::from_raw_parts(ptr.as_ptr(), 8),
+                &[7, 7, 7, 7, 0, 0, 0, 0]
+            );
+            assert!(a.check(ptr, big));
+
+            // overflowing the grown block is caught when it's shrunk
+            *ptr.as_ptr().add(64) = 0;
+            let ptr = a.shrink(ptr, big, small).unwrap();
+            assert_eq!(a.handler().load(Ordering::SeqCst), 1);
+            assert_eq!(*ptr.as_ptr().add(3), 7);
+
+            // changing the alignment moves the block
+            let aligned = Layout::from_size_align(16, 64).unwrap();
+            let ptr = a.realloc(ptr, small, aligned).unwrap();
+            assert_eq!(ptr.as_ptr() as usize % 64, 0);
+            assert_eq!(*ptr.as_ptr().add(3), 7);
+            a.dealloc(ptr, aligned);
+        }
+        assert_eq!(a.handler().load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer overflow")]
+    fn panics_by_default() {
+        let a = Guarded::new();
+        let layout = Layout::new::<u32>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            ptr.as_ptr().add(4).write(0);
+            a.dealloc(ptr, layout);
+        }
+    }
+}
+
+#[cfg(feature = "poison")]
+mod poison_tests {
+    use core::{alloc::Layout, sync::atomic::AtomicUsize};
+    use memapi::{
+        poison::{Poison, ALLOC_POISON, FREE_POISON},
+        Alloc, DefaultAlloc,
+    };
+    use std::sync::atomic::Ordering;
+
+    unsafe fn bytes<'a>(ptr: core::ptr::NonNull<u8>, len: usize) -> &'a [u8] {
+        core::slice::from_raw_parts(ptr.as_ptr(), len)
+    }
+
+    #[test]
+    fn poisons_fresh_and_freed_blocks() {
+        let a = Poison::new().with_quarantine::<1>();
+        let layout = Layout::new::<[u8; 16]>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            assert_eq!(bytes(ptr, 16), &[ALLOC_POISON; 16]);
+            let zeroed = a.alloc_zeroed(layout).unwrap();
+            assert_eq!(bytes(zeroed, 16), &[0; 16]);
+            a.dealloc(zeroed, layout);
+
+            // the quarantine keeps the freed block around, so it can still be read here
+            a.dealloc(ptr, layout);
+            assert_eq!(bytes(ptr, 16), &[FREE_POISON; 16]);
+        }
+        assert_eq!(a.quarantined(), 1);
+        a.flush();
+        assert_eq!(a.quarantined(), 0);
+    }
+
+    #[test]
+    fn custom_patterns() {
+        let a = Poison::new().with_patterns(1, 2).with_quarantine::<4>();
+        let layout = Layout::new::<u32>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            assert_eq!(bytes(ptr, 4), &[1; 4]);
+            a.dealloc(ptr, layout);
+            assert_eq!(bytes(ptr, 4), &[2; 4]);
+        }
+    }
+
+    #[test]
+    fn detects_use_after_free() {
+        let a = Poison::with_handler_in(AtomicUsize::new(0), DefaultAlloc).with_quarantine::<2>();
+        let layout = Layout::new::<u64>();
+        unsafe {
+            let stale = a.alloc(layout).unwrap();
+            a.dealloc(stale, layout);
+            stale.as_ptr().write(0);
+
+            let others = [a.alloc(layout).unwrap(), a.alloc(layout).unwrap()];
+            a.dealloc(others[0], layout);
+            assert_eq!(a.handler().load(Ordering::SeqCst), 0);
+            // this pushes the stale block out of the quarantine
+            a.dealloc(others[1], layout);
+        }
+        assert_eq!(a.handler().load(Ordering::SeqCst), 1);
+        assert_eq!(a.quarantined(), 2);
+    }
+
+    #[test]
+    fn resizing_moves_and_poisons() {
+        let a = Poison::new().with_quarantine::<1>();
+        let small = Layout::new::<[u8; 4]>();
+        let big = Layout::new::<[u8; 8]>();
+        unsafe {
+            let ptr = a.alloc(small).unwrap();
+            ptr.as_ptr().write_bytes(7, 4);
+            let grown = a.grow(ptr, small, big).unwrap();
+            assert_ne!(grown, ptr);
+            assert_eq!(&bytes(grown, 8)[..4], &[7; 4]);
+            assert_eq!(&bytes(grown, 8)[4..], &[ALLOC_POISON; 4]);
+            assert_eq!(bytes(ptr, 4), &[FREE_POISON; 4]);
+
+            let shrunk = a.shrink(grown, big, small).unwrap();
+            assert_eq!(bytes(shrunk, 4), &[7; 4]);
+            a.dealloc(shrunk, small);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "use after free")]
+    fn panics_on_drop_by_default() {
+        let a = Poison::new().with_quarantine::<8>();
+        let layout = Layout::new::<u8>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            a.dealloc(ptr, layout);
+            ptr.as_ptr().write(0);
+        }
+    }
+
+    #[cfg(feature = "owned")]
+    #[test]
+    fn exposes_uninit_slots_of_owned_buf() {
+        use memapi::owned::OwnedBuf;
+
+        let a = Poison::new();
+        let mut buf = OwnedBuf::<u8, _>::new_in(4, &a).unwrap();
+        buf.try_init_next(3).unwrap();
+        assert_eq!(buf.init_buf(), &[3]);
+        // reading past the initialized part would see poison, not zeros
+        unsafe {
+            assert_eq!(buf.uninit_buf()[0].assume_init(), ALLOC_POISON);
+        }
+        assert_eq!(buf.remove_last(), Some(3));
+    }
+}
+
+#[cfg(feature = "checked")]
+mod checked_tests {
+    use core::{alloc::Layout, cell::RefCell};
+    use memapi::{
+        checked::{Checked, TABLE_SLOTS},
+        error::{AllocError, Violation, ViolationKind},
+        Alloc, DefaultAlloc, Owns,
+    };
+
+    fn recording() -> RefCell<Vec<ViolationKind>> {
+        RefCell::new(Vec::new())
+    }
+
+    #[test]
+    fn tracks_live_blocks() {
+        let a = Checked::new();
+        let layout = Layout::new::<[u32; 4]>();
+        let ptr = a.alloc(layout).unwrap();
+        assert_eq!(a.live(), 1);
+        assert_eq!(a.layout_of(ptr), Some(layout));
+        assert!(a.owns(ptr, layout));
+        assert!(!a.owns(ptr, Layout::new::<u32>()));
+        unsafe {
+            let ptr = a.grow(ptr, layout, Layout::new::<[u32; 8]>()).unwrap();
+            assert_eq!(a.layout_of(ptr), Some(Layout::new::<[u32; 8]>()));
+            let ptr = a.shrink(ptr, Layout::new::<[u32; 8]>(), layout).unwrap();
+            a.dealloc(ptr, layout);
+            assert_eq!(a.layout_of(ptr), None);
+        }
+        assert_eq!(a.live(), 0);
+    }
+
+    #[cfg(feature = "pool")]
+    #[test]
+    fn accepts_sizes_up_to_usable_size() {
+        use memapi::pool::Pool;
+
+        let seen = recording();
+        let pool = Pool::new(Layout::new::<[u64; 4]>()).unwrap();
+        let a = Checked::with_handler_in(|v: &Violation| seen.borrow_mut().push(v.kind), pool);
+        let layout = Layout::new::<u64>();
+        let ptr = a.alloc(layout).unwrap();
+        unsafe {
+            let usable = Layout::from_size_align(a.usable_size(ptr, layout), 8).unwrap();
+            assert_eq!(usable.size(), 32);
+            assert!(a.owns(ptr, usable));
+            assert!(!a.owns(ptr, Layout::new::<[u64; 5]>()));
+            assert!(!a.owns(ptr, Layout::new::<[u32; 4]>()));
+            a.dealloc(ptr, Layout::new::<[u64; 3]>());
+        }
+        assert!(seen.borrow().is_empty());
+        assert_eq!(a.live(), 0);
+    }
+
+    #[test]
+    fn reports_layout_mismatch() {
+        let seen = recording();
+        let a =
+            Checked::with_handler_in(|v: &Violation| seen.borrow_mut().push(v.kind), DefaultAlloc);
+        let layout = Layout::new::<[u8; 16]>();
+        let wrong = Layout::new::<[u8; 8]>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            ptr.as_ptr().write_bytes(5, 16);
+            // the grow goes ahead using the recorded layout
+            let ptr = a.realloc(ptr, wrong, Layout::new::<[u8; 32]>()).unwrap();
+            assert_eq!(*ptr.as_ptr().add(15), 5);
+            a.dealloc(ptr, wrong);
+        }
+        assert_eq!(
+            *seen.borrow(),
+            [
+                ViolationKind::LayoutMismatch(layout),
+                ViolationKind::LayoutMismatch(Layout::new::<[u8; 32]>())
+            ]
+        );
+        assert_eq!(a.live(), 0);
+    }
+
+    #[test]
+    fn reports_double_free_and_unknown_pointers() {
+        let seen = recording();
+        let a =
+            Checked::with_handler_in(|v: &Violation| seen.borrow_mut().push(v.kind), DefaultAlloc);
+        let layout = Layout::new::<u64>();
+        let foreign = DefaultAlloc.alloc(layout).unwrap();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            a.dealloc(ptr, layout);
+            a.dealloc(ptr, layout);
+            a.dealloc(foreign, layout);
+            assert_eq!(
+                a.grow(foreign, layout, Layout::new::<[u64; 2]>()),
+                Err(AllocError::Other("pointer does not refer to a live block"))
+            );
+            DefaultAlloc.dealloc(foreign, layout);
+        }
+        assert_eq!(
+            *seen.borrow(),
+            [
+                ViolationKind::DoubleFree,
+                ViolationKind::UnknownPointer,
+                ViolationKind::UnknownPointer
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_once_the_table_is_full() {
+        assert_eq!(TABLE_SLOTS, 512);
+        let a = Checked::new().with_slots::<2>();
+        let layout = Layout::new::<u64>();
+        let first = a.alloc(layout).unwrap();
+        let second = a.alloc(layout).unwrap();
+        assert_eq!(
+            a.alloc(layout),
+            Err(AllocError::Other("the block table is full"))
+        );
+        unsafe {
+            a.dealloc(first, layout);
+            // the record of the deallocated block is reused
+            let third = a.alloc(layout).unwrap();
+            a.dealloc(second, layout);
+            a.dealloc(third, layout);
+        }
+        assert_eq!(a.live(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "double free")]
+    fn panics_by_default() {
+        let a = Checked::new();
+        let layout = Layout::new::<u8>();
+        unsafe {
+            let ptr = a.alloc(layout).unwrap();
+            a.dealloc(ptr, layout);
+            a.dealloc(ptr, layout);
+        }
+    }
+
+    #[cfg(feature = "owned")]
+    #[test]
+    fn owned_buf_uses_matching_layouts() {
+        use memapi::owned::OwnedBuf;
+
+        let a = Checked::new();
+        let mut buf = OwnedBuf::new_unallocated_in(&a);
+        for i in 0..100_u32 {
+            buf.init_next_grow(i).unwrap();
+        }
+        assert_eq!(a.live(), 1);
+        drop(buf.into_iter());
+        assert_eq!(a.live(), 0);
+    }
+}
+
+#[cfg(feature = "leak_check")]
+mod leak_check_tests {
+    use core::alloc::Layout;
+    use memapi::{leak_check::LeakCheck, Alloc};
+
+    #[test]
+    fn groups_by_call_site() {
+        let a = LeakCheck::new();
+        let small = Layout::new::<u32>();
+        let big = Layout::new::<[u8; 64]>();
+        let mut ptrs = Vec::new();
+        let small_line = line!() + 2;
+        for _ in 0..3 {
+            ptrs.push((a.alloc(small).unwrap(), small));
+        }
+        let big_line = line!() + 1;
+        ptrs.push((a.alloc(big).unwrap(), big));
+
+        let report = a.report();
+        assert_eq!((report.count(), report.bytes()), (4, 76));
+        assert_eq!(report.leaks.len(), 2);
+        assert_eq!(report.leaks[0].location.line(), big_line);
+        assert_eq!(report.leaks[1].location.line(), small_line);
+        assert_eq!((report.leaks[1].count, report.leaks[1].bytes), (3, 12));
+        let text = report.to_string();
+        assert!(text.starts_with("4 live allocations (76 bytes)"));
+        assert!(text.contains(&format!(
+            "12 bytes in 3 blocks allocated at {}:{small_line}",
+            file!()
+        )));
+
+        for (ptr, layout) in ptrs {
+            unsafe {
+                a.dealloc(ptr, layout);
+            }
+        }
+        assert!(a.report().is_empty());
+        a.assert_no_leaks();
+    }
+
+    #[test]
+    fn resizing_keeps_call_site() {
+        let a = LeakCheck::new();
+        let old = Layout::new::<[u8; 8]>();
+        let new = Layout::new::<[u8; 128]>();
+        let line = line!() + 1;
+        let ptr = a.alloc(old).unwrap();
+        let ptr = unsafe { a.grow(ptr, old, new).unwrap() };
+        let report = a.report();
+        assert_eq!(report.leaks[0].location.line(), line);
+        assert_eq!((report.count(), report.bytes()), (1, 128));
+        unsafe {
+            a.dealloc(ptr, new);
+        }
+        assert_eq!(a.live(), 0);
+    }
+
+    #[cfg(feature = "alloc_ext")]
+    #[test]
+    fn attributes_through_track_caller_helpers() {
+        use memapi::AllocExt;
+
+        let a = LeakCheck::new();
+        let line = line!() + 1;
+        let ptr = a.alloc_init::<u64, _>(|p| unsafe { p.write(5) }).unwrap();
+        assert_eq!(a.report().leaks[0].location.line(), line);
+        unsafe {
+            a.dealloc(ptr.cast(), Layout::new::<u64>());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "1 live allocations (2 bytes)")]
+    fn reports_on_drop() {
+        let a = LeakCheck::new().report_on_drop();
+        a.alloc(Layout::new::<u16>()).unwrap();
+    }
+}
+
+#[cfg(feature = "zeroizing")]
+mod zeroizing_tests {
+    use core::{alloc::Layout, cell::Cell, ptr::NonNull};
+    use memapi::{error::AllocError, zeroizing::Zeroizing, Alloc, DefaultAlloc};
+
+    /// Counts deallocated blocks which were fully zeroed.
+    #[derive(Default)]
+    struct Inspect {
+        wiped: Cell<usize>,
+        dirty: Cell<usize>,
+    }
+
+    impl Alloc for Inspect {
+        fn alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+            DefaultAlloc.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+            let bytes = core::slice::from_raw_parts(ptr.as_ptr(), layout.size());
+            let counter = if bytes.iter().all(|&b| b == 0) {
+                &self.wiped
+            } else {
+                &self.dirty
+            };
+            counter.set(counter.get() + 1);
+            DefaultAlloc.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn wipes_freed_and_moved_blocks() {
+        let inspect = Inspect::default();
+        let a = Zeroizing::new_in(&inspect);
+        let small = Layout::new::<[u8; 8]>();
+        let big = Layout::new::<[u8; 32]>();
+        unsafe {
+            let ptr = a.alloc(small).unwrap();
+            ptr.as_ptr().write_bytes(0x5E, 8);
+            let ptr = a.grow(ptr, small, big).unwrap();
+            assert_eq!(*ptr.as_ptr().add(7), 0x5E);
+            let ptr = a.shrink(ptr, big, small).unwrap();
+            let ptr = a.realloc(ptr, small, big).unwrap();
+            assert_eq!(*ptr.as_ptr(), 0x5E);
+            a.dealloc(ptr, big);
+        }
+        assert_eq!((inspect.wiped.get(), inspect.dirty.get()), (4, 0));
+    }
+
+    #[cfg(feature = "owned")]
+    #[test]
+    fn wipes_owned_secrets() {
+        use memapi::owned::{HeapVal, OwnedBuf};
+
+        let inspect = Inspect::default();
+        let a = Zeroizing::new_in(&inspect);
+        let secret = HeapVal::new_in([0xAB_u8; 16], &a).unwrap();
+        secret.drop_and_dealloc();
+
+        let mut buf = OwnedBuf::new_unallocated_in(&a);
+        for b in b"hunter2hunter2" {
+            buf.init_next_grow(*b).unwrap();
+        }
+        drop(buf.into_iter());
+        assert!(inspect.wiped.get() >= 2);
+        assert_eq!(inspect.dirty.get(), 0);
+    }
+
+    #[cfg(feature = "alloc_ext")]
+    #[test]
+    fn zero_helpers_wipe() {
+        use memapi::AllocExt;
+
+        let inspect = Inspect::default();
+        unsafe {
+            let ptr = inspect.alloc_write([7_u64; 4]).unwrap();
+            inspect.drop_zero_and_dealloc(ptr);
+            let ptr = inspect.alloc(Layout::new::<u32>()).unwrap();
+            ptr.as_ptr().write_bytes(1, 4);
+            inspect.zero_and_dealloc(ptr, Layout::new::<u32>());
+        }
+        assert_eq!((inspect.wiped.get(), inspect.dirty.get()), (2, 0));
+    }
+}
+
+#[cfg(all(feature = "jemalloc", not(miri)))]
+mod jemalloc_tests {
+    use core::{alloc::Layout, slice};
+    use memapi::{ffi::jem::usable_size, jemalloc::Jemalloc, type_props::SizedProps, Alloc};
+
+    #[test]
+    fn alloc_and_dealloc_basic() {
+        let alloc = Jemalloc;
+        let layout = Layout::from_size_align(64, 8).unwrap();
+
+        unsafe {
+            let ptr = alloc.alloc(layout).unwrap();
+            // we can write into the block
+            ptr.write_bytes(0xAB, layout.size());
+            alloc.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn alloc_zeroed_is_really_zeroed() {
+        let alloc = Jemalloc;
+        let size = 128;
+        let layout = Layout::from_size_align(size, 8).unwrap();
+
+        unsafe {
+            let ptr = alloc.alloc_zeroed(layout).unwrap();
+            // treat as byte slice
+            let buf = slice::from_raw_parts(ptr.as_ptr(), size);
+            assert!(
+                buf.iter().all(|&b| b == 0),
+                "alloc_zeroed must produce all-zero bytes"
+            );
+            alloc.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn usable_size_at_least_requested() {
+        let alloc = Jemalloc;
+        let size = 100;
+        let layout = Layout::from_size_align(size, 16).unwrap();
+
+        unsafe {
+            let ptr = alloc.alloc(layout).unwrap();
+            let usable = usable_size(ptr.as_ptr());
+            assert!(
+                usable >= size,
+                "usable_size {} should be >= requested {}",
+                usable,
+                size
+            );
+            alloc.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn alloc_sized_reports_usable_size() {
+        let alloc = Jemalloc;
+        // jemalloc rounds this up to a size class.
+        let layout = Layout::from_size_align(100, 8).unwrap();
+
+        unsafe {
+            let block = alloc.alloc_sized(layout).unwrap();
+            let len = block.as_ref().len();
+            assert_eq!(len, usable_size(block.cast::<u8>().as_ptr()));
+            assert!(len >= layout.size());
+
+            // the whole block is usable and may be freed with its usable size.
+            block.cast::<u8>().as_ptr().write_bytes(0xAB, len);
+            alloc.dealloc(
+                block.cast(),
+                Layout::from_size_align(len, layout.align()).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn realloc_preserves_initial_contents() {
+        let alloc = Jemalloc;
+        let old_count = 4;
+        let old_layout = Layout::array::<u32>(old_count).unwrap();
+
+        unsafe {
+            // allocate and write a pattern
+            let ptr = alloc.alloc(old_layout).unwrap().cast();
+            for i in 0..old_count {
+                ptr.add(i).write(i as u32 + 1);
+            }
+
+            // grow to twice as many elements
+            let new_size = old_count * 2 * u32::SZ;
+            let new_layout = Layout::from_size_align_unchecked(new_size, u32::ALIGN);
+            let new_ptr = alloc
+                .realloc(ptr.cast(), old_layout, new_layout)
+                .unwrap()
+                .cast();
+
+            // original data should be intact
+            for i in 0..old_count {
+                let v: u32 = new_ptr.add(i).read();
+                assert_eq!(v, i as u32 + 1);
+            }
+
+            // cleanup
             let new_layout = Layout::from_size_align(new_size, old_layout.align()).unwrap();
             alloc.dealloc(new_ptr.cast(), new_layout);
         }
     }
 }
+
+#[cfg(all(feature = "mimalloc", not(miri)))]
+mod mimalloc_tests {
+    use core::alloc::Layout;
+    use memapi::{ffi::mim::usable_size, mimalloc::MiMalloc, Alloc};
+
+    #[test]
+    fn alloc_sized_reports_usable_size() {
+        let alloc = MiMalloc;
+        let layout = Layout::from_size_align(100, 8).unwrap();
+
+        unsafe {
+            let block = alloc.alloc_sized(layout).unwrap();
+            let len = block.as_ref().len();
+            assert_eq!(len, usable_size(block.cast::<u8>().as_ptr()));
+            assert!(len >= layout.size());
+
+            let new = Layout::from_size_align(len + 1, layout.align()).unwrap();
+            let grown = alloc
+                .grow_sized(
+                    block.cast(),
+                    Layout::from_size_align(len, layout.align()).unwrap(),
+                    new,
+                )
+                .unwrap();
+            assert!(grown.as_ref().len() >= new.size());
+            alloc.dealloc(grown.cast(), new);
+        }
+    }
+
+    #[test]
+    fn owns_its_heap_region() {
+        use memapi::Owns;
+
+        let alloc = MiMalloc;
+        let layout = Layout::new::<[u64; 4]>();
+        let ptr = alloc.alloc(layout).unwrap();
+        let stack = [0u64; 4];
+        assert!(alloc.owns(ptr, layout));
+        assert!(!alloc.owns(core::ptr::NonNull::from(&stack).cast(), layout));
+        unsafe {
+            alloc.dealloc(ptr, layout);
+        }
+    }
+}